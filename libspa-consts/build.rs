@@ -119,6 +119,8 @@ fn run_bindgen(libs: &system_deps::Dependencies) {
         "spa_direction",
         "spa_param_availability",
         "spa_control_type",
+        "spa_node_event",
+        "spa_node_command",
         "spa_meta_type",
         "spa_param_tag",
         "spa_param_buffers",
@@ -130,6 +132,7 @@ fn run_bindgen(libs: &system_deps::Dependencies) {
         "spa_param_process_latency",
         "pw_link_state",
         "pw_node_state",
+        "pw_endpoint_link_state",
         "spa_param_port_config_mode",
         "spa_bluetooth_audio_codec",
         "spa_audio_format",
@@ -101,6 +101,52 @@ pub enum SpaControlType {
     _SPA_CONTROL_LAST = 5,
 }
 #[repr(u32)]
+#[derive(
+    Debug, Copy, Clone, Hash, PartialEq, Eq, num_derive :: FromPrimitive, num_derive :: ToPrimitive,
+)]
+pub enum SpaNodeEvent {
+    Error = 0,
+    Buffering = 1,
+    RequestRefresh = 2,
+    #[doc = "< Ask the driver to start processing
+  the graph"]
+    RequestProcess = 3,
+    #[doc = "< User defined event"]
+    User = 4,
+}
+#[repr(u32)]
+#[derive(
+    Debug, Copy, Clone, Hash, PartialEq, Eq, num_derive :: FromPrimitive, num_derive :: ToPrimitive,
+)]
+pub enum SpaNodeCommand {
+    #[doc = "< suspend a node, this removes all configured
+ formats and closes any devices"]
+    Suspend = 0,
+    #[doc = "< pause a node. this makes it stop emitting
+ scheduling events"]
+    Pause = 1,
+    #[doc = "< start a node, this makes it start emitting
+ scheduling events"]
+    Start = 2,
+    Enable = 3,
+    Disable = 4,
+    Flush = 5,
+    Drain = 6,
+    Marker = 7,
+    #[doc = "< begin a set of parameter enumerations or
+ configuration that require the device to
+ remain opened, like query formats and then
+ set a format"]
+    ParamBegin = 8,
+    #[doc = "< end a transaction"]
+    ParamEnd = 9,
+    #[doc = "< Sent to a driver when some other node emitted
+ the RequestProcess event."]
+    RequestProcess = 10,
+    #[doc = "< User defined command"]
+    User = 11,
+}
+#[repr(u32)]
 #[doc = " Different IO area types"]
 #[derive(
     Debug, Copy, Clone, Hash, PartialEq, Eq, num_derive :: FromPrimitive, num_derive :: ToPrimitive,
@@ -1297,3 +1343,13 @@ pub enum PwNodeState {
     #[doc = "< the node is running"]
     Running = 3,
 }
+#[repr(i32)]
+#[derive(
+    Debug, Copy, Clone, Hash, PartialEq, Eq, num_derive :: FromPrimitive, num_derive :: ToPrimitive,
+)]
+pub enum PwEndpointLinkState {
+    Error = -1,
+    Preparing = 0,
+    Inactive = 1,
+    Active = 2,
+}
@@ -1,38 +1,56 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput};
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
 
 #[proc_macro_derive(EventDeserialize)]
 pub fn event_deserialize(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = input.ident;
 
-    let out = if let Data::Enum(ref e) = input.data {
-        e.variants.iter().map(|variant| {
-            let variant = &variant.ident;
-            quote!(events::#variant::OPCODE => Self::#variant(
-                Deserialize::deserialize(pod, fds).map_err(|error| {
-                    EventDeserializeError {
-                        interface: Self::INTERFACE,
-                        event: stringify!(#variant),
-                        error,
-                    }
-                })?
-            ))
-        })
+    let variants: Vec<_> = if let Data::Enum(ref e) = input.data {
+        // Unit variants are pseudo events, they are never sent by the daemon
+        e.variants
+            .iter()
+            .filter(|variant| !matches!(variant.fields, Fields::Unit))
+            .map(|variant| &variant.ident)
+            .collect()
     } else {
         unimplemented!("Not a struct")
     };
 
+    let out = variants.iter().map(|variant| {
+        quote!(events::#variant::OPCODE => Ok(Self::#variant(
+            Deserialize::deserialize(pod, fds).map_err(|error| {
+                EventDeserializeError {
+                    interface: Self::INTERFACE,
+                    event: stringify!(#variant),
+                    error,
+                }
+            })?
+        )))
+    });
+    let since = variants
+        .iter()
+        .map(|variant| quote!(events::#variant::OPCODE => events::#variant::SINCE));
+
     let expanded = quote! {
         impl #name {
-            pub fn deserialize(opcode: u8, pod: &mut pod::PodDeserializer, fds: &[std::os::fd::RawFd]) -> Result<Self, EventDeserializeError> {
-                let mut this = match opcode {
+            pub fn deserialize(opcode: u8, pod: &mut pod::PodDeserializer, fds: &mut [Option<std::os::fd::OwnedFd>]) -> Result<Self, crate::Error> {
+                match opcode {
                     #(#out,)*
-                    _ => todo!("opcode: {opcode}"),
-                };
+                    _ => Err(crate::Error::UnknownOpcode {
+                        interface: Self::INTERFACE,
+                        opcode,
+                    }),
+                }
+            }
 
-                Ok(this)
+            /// Interface version that introduced the event `opcode`
+            pub fn since(opcode: u8) -> u32 {
+                match opcode {
+                    #(#since,)*
+                    _ => 0,
+                }
             }
         }
     };
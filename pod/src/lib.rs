@@ -1,6 +1,6 @@
 pub mod dbg_print;
 pub mod serialize;
-use std::os::fd::RawFd;
+use std::os::fd::{OwnedFd, RawFd};
 
 pub use serialize::Builder;
 
@@ -36,10 +36,13 @@ impl From<u32> for Id {
 }
 
 /// A file descriptor in a pod
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///
+/// `id` is the index of the fd in the list of fds sent along with the message,
+/// `fd` is the received fd itself, it gets closed when dropped.
+#[derive(Debug)]
 pub struct Fd {
     pub id: i64,
-    pub fd: Option<RawFd>,
+    pub fd: Option<OwnedFd>,
 }
 
 impl Fd {
@@ -1,7 +1,7 @@
 #![allow(clippy::single_match)]
 
 use std::ffi::CStr;
-use std::io::{self, Read};
+use std::io::Read;
 use std::os::fd::{AsRawFd, BorrowedFd, FromRawFd, IntoRawFd};
 use std::ptr::NonNull;
 
@@ -14,7 +14,6 @@ use libspa_consts::{
 use ripewire::connection::MessageBuffer;
 use ripewire::context::Context;
 use ripewire::global_list::GlobalList;
-use ripewire::memory_registry::MemoryRegistry;
 use ripewire::object_map::ObjectType;
 use ripewire::protocol::pw_client_node::methods::{
     NodeInfoChangeMask, PortInfoChangeMask, PortUpdateChangeMask,
@@ -72,17 +71,17 @@ fn properties() -> PwDictionary {
 }
 
 pub fn run_rust() {
-    let mut ctx = Context::<PipewireState>::connect("/run/user/1000/pipewire-0").unwrap();
+    let mut ctx = Context::<PipewireState>::connect_default().unwrap();
     let core = ctx.core();
     let client = ctx.client();
 
-    core.hello(&mut ctx);
+    core.hello(&mut ctx).unwrap();
 
-    client.update_properties(&mut ctx, properties());
+    client.update_properties(&mut ctx, properties()).unwrap();
 
-    let registry = core.get_registry(&mut ctx);
+    let registry = core.get_registry(&mut ctx).unwrap();
 
-    core.sync(&mut ctx, 0, 0);
+    core.sync(&mut ctx, 0, 0).unwrap();
 
     ctx.set_object_callback(&core, PipewireState::core_event);
     ctx.set_object_callback(&client, PipewireState::client_event);
@@ -96,7 +95,6 @@ pub fn run_rust() {
         state: PipewireState {
             registry,
             globals: GlobalList::default(),
-            mems: MemoryRegistry::default(),
         },
     };
 
@@ -110,17 +108,20 @@ pub fn run_rust() {
             ),
             move |_, _, state| {
                 loop {
-                    let msg = state.ctx.rcv_msg(&mut buffer);
+                    let messages = state.ctx.rcv_msg(&mut buffer);
 
-                    if let Err(err) = &msg {
-                        if err.kind() == io::ErrorKind::WouldBlock {
+                    if let Err(err) = &messages {
+                        if err.is_would_block() {
                             break;
                         }
                     }
 
-                    let msg = msg.unwrap();
-
-                    state.ctx.dispatch_event(&mut state.state, msg);
+                    for msg in messages.unwrap() {
+                        state
+                            .ctx
+                            .dispatch_event(&mut state.state, msg.unwrap())
+                            .unwrap();
+                    }
                 }
 
                 Ok(PostAction::Continue)
@@ -128,8 +129,13 @@ pub fn run_rust() {
         )
         .unwrap();
 
-    ev.run(None, &mut state, |_state| {
-        //
+    ev.run(None, &mut state, |state| {
+        // Socket being full is fine, remaining messages will be sent on next iteration
+        if let Err(err) = state.ctx.flush() {
+            if !err.is_would_block() {
+                panic!("{err}");
+            }
+        }
     })
     .unwrap();
 }
@@ -142,14 +148,13 @@ struct CalloopState {
 struct PipewireState {
     registry: PwRegistry,
     globals: GlobalList,
-    mems: MemoryRegistry,
 }
 
 impl PipewireState {
     pub fn core_event(
         &mut self,
         ctx: &mut Context<Self>,
-        core: PwCore,
+        _core: PwCore,
         core_event: pw_core::Event,
     ) {
         // dbg!(&core_event);
@@ -159,15 +164,6 @@ impl PipewireState {
                     self.done(ctx);
                 }
             }
-            pw_core::Event::AddMem(add_mem) => {
-                self.mems.add_mem(&add_mem);
-            }
-            pw_core::Event::RemoveMem(remove_mem) => {
-                self.mems.remove_mem(&remove_mem);
-            }
-            pw_core::Event::Ping(ping) => {
-                core.pong(ctx, ping.id, ping.seq);
-            }
             pw_core::Event::Error(error) => {
                 dbg!(ctx.object_type(&ObjectId::new(error.id)));
                 dbg!(error);
@@ -195,7 +191,7 @@ impl PipewireState {
             pw_node::Event::Info(msg) => {
                 for param in msg.params.iter() {
                     if let SpaEnum::Value(id) = param.id {
-                        node.enum_param(ctx, id);
+                        node.enum_param(ctx, id).unwrap();
                     }
                 }
 
@@ -273,12 +269,13 @@ impl PipewireState {
                     }
                 }
             }
+            pw_node::Event::Removed => {}
         }
     }
 
     pub fn client_node_event(
         &mut self,
-        _ctx: &mut Context<Self>,
+        ctx: &mut Context<Self>,
         _client: PwClientNode,
         client_node_event: pw_client_node::Event,
     ) {
@@ -286,7 +283,7 @@ impl PipewireState {
 
         match client_node_event {
             pw_client_node::Event::Transport(msg) => {
-                let mem = self.mems.get(&msg.memid).unwrap();
+                let mem = ctx.memory_registry().get(&msg.memid).unwrap();
 
                 if false {
                     let mut buf = vec![0; msg.size as usize];
@@ -328,7 +325,7 @@ impl PipewireState {
             pw_device::Event::Info(msg) => {
                 for param in msg.params.iter() {
                     if let SpaEnum::Value(id) = param.id {
-                        device.enum_param(ctx, id);
+                        device.enum_param(ctx, id).unwrap();
                     }
                 }
 
@@ -357,6 +354,7 @@ impl PipewireState {
                     }
                 }
             }
+            pw_device::Event::Removed => {}
         }
     }
 
@@ -381,71 +379,76 @@ impl PipewireState {
             .iter()
             .filter(|global| global.interface == ObjectType::Node)
         {
-            let node: PwNode = self.registry.bind(ctx, node);
+            let node: PwNode = self.registry.bind_untyped(ctx, node).unwrap();
             ctx.set_object_callback(&node, Self::node_event);
         }
 
         if let Some(global) = client {
-            let client: PwClient = self.registry.bind(ctx, global);
+            let client: PwClient = self.registry.bind_untyped(ctx, global).unwrap();
 
-            client.get_permissions(ctx, 0, 50);
+            client.get_permissions(ctx, 0, 50).unwrap();
         }
 
         if let Some(global) = device {
-            let device: PwDevice = self.registry.bind(ctx, global);
-
-            device.set_param(
-                ctx,
-                // TODO: Not very fun to write
-                pod::Builder::with(|b| {
-                    b.write_object_with(
-                        SpaType::ObjectParamRoute,
-                        SpaParamType::Route as u32,
-                        |b| {
-                            b.write_property(SpaParamRoute::Index as u32, 0, |b| {
-                                b.write_u32(4);
-                            });
-                            b.write_property(SpaParamRoute::Device as u32, 0, |b| {
-                                b.write_u32(4);
-                            });
-                            b.write_property(SpaParamRoute::Props as u32, 0, |b| {
-                                b.write_object_with(
-                                    SpaType::ObjectProps,
-                                    SpaParamType::Route as u32,
-                                    |b| {
-                                        b.write_property(SpaProp::Mute as u32, 0, |b| {
-                                            b.write_bool(false);
-                                        });
-                                        b.write_property(SpaProp::Volume as u32, 0, |b| {
-                                            b.write_float(0.1);
-                                        });
-                                    },
-                                );
-                            });
-                        },
-                    );
-                }),
-            );
+            let device: PwDevice = self.registry.bind_untyped(ctx, global).unwrap();
+
+            device
+                .set_param(
+                    ctx,
+                    // TODO: Not very fun to write
+                    pod::Builder::with(|b| {
+                        b.write_object_with(
+                            SpaType::ObjectParamRoute,
+                            SpaParamType::Route as u32,
+                            |b| {
+                                b.write_property(SpaParamRoute::Index as u32, 0, |b| {
+                                    b.write_u32(4);
+                                });
+                                b.write_property(SpaParamRoute::Device as u32, 0, |b| {
+                                    b.write_u32(4);
+                                });
+                                b.write_property(SpaParamRoute::Props as u32, 0, |b| {
+                                    b.write_object_with(
+                                        SpaType::ObjectProps,
+                                        SpaParamType::Route as u32,
+                                        |b| {
+                                            b.write_property(SpaProp::Mute as u32, 0, |b| {
+                                                b.write_bool(false);
+                                            });
+                                            b.write_property(SpaProp::Volume as u32, 0, |b| {
+                                                b.write_float(0.1);
+                                            });
+                                        },
+                                    );
+                                });
+                            },
+                        );
+                    }),
+                )
+                .unwrap();
 
             ctx.set_object_callback(&device, Self::device_event);
         }
 
         if false {
-            let client_node: PwClientNode = ctx.core().create_object(
-                ctx,
-                pw_core::methods::CreateObject {
-                    factory_name: "client-node".into(),
-                    interface: "PipeWire:Interface:ClientNode".into(),
-                    version: 3,
-                    properties: PwDictionary::from_dict([
-                        ("application.name", "rustypipe"),
-                        ("media.type", "Midi"),
-                        ("format.dsp", "8 bit raw midi"),
-                        ("stream.is-live", "true"),
-                    ]),
-                    new_id: 0,
-                },
-            );
+            let client_node: PwClientNode = ctx
+                .core()
+                .create_object(
+                    ctx,
+                    pw_core::methods::CreateObject {
+                        factory_name: "client-node".into(),
+                        interface: "PipeWire:Interface:ClientNode".into(),
+                        version: 3,
+                        properties: PwDictionary::from_dict([
+                            ("application.name", "rustypipe"),
+                            ("media.type", "Midi"),
+                            ("format.dsp", "8 bit raw midi"),
+                            ("stream.is-live", "true"),
+                        ]),
+                        new_id: 0,
+                    },
+                )
+                .unwrap();
 
             let id = client_node.id().protocol_id();
 
@@ -7,14 +7,17 @@ use std::{
     io::{IoSlice, IoSliceMut},
     mem::MaybeUninit,
     os::{
-        fd::{BorrowedFd, IntoRawFd, RawFd},
+        fd::{AsFd, OwnedFd},
         unix::net::{UnixListener, UnixStream},
     },
     sync::{Arc, Mutex},
 };
 
 use pod::deserialize::PodDeserializerKind;
-use ripewire::{connection::Message, object_map::ObjectType};
+use ripewire::{
+    connection::{Message, PeerFormat, ProtocolVersion},
+    object_map::ObjectType,
+};
 use rustix::net::{
     RecvAncillaryBuffer, RecvAncillaryMessage, RecvFlags, SendAncillaryBuffer,
     SendAncillaryMessage, SendFlags,
@@ -25,7 +28,7 @@ pub const MAX_BUFFER_SIZE: usize = 500000;
 pub const MAX_FDS: usize = 1024;
 pub const MAX_FDS_MSG: usize = 28;
 
-fn recvmsg<'a>(stream: &UnixStream, buffer: &'a mut [u8]) -> (&'a [u8], VecDeque<RawFd>) {
+fn recvmsg<'a>(stream: &UnixStream, buffer: &'a mut [u8]) -> (&'a [u8], VecDeque<OwnedFd>) {
     let mut space = [MaybeUninit::uninit(); rustix::cmsg_space!(ScmRights(MAX_FDS_MSG))];
     let mut cmsgs = RecvAncillaryBuffer::new(&mut space);
     let mut iov = [IoSliceMut::new(buffer)];
@@ -43,18 +46,14 @@ fn recvmsg<'a>(stream: &UnixStream, buffer: &'a mut [u8]) -> (&'a [u8], VecDeque
 
             _ => todo!(),
         })
-        .flatten()
-        .map(|fd| fd.into_raw_fd());
+        .flatten();
     fds.extend(received_fds);
 
     (&buffer[..len], fds)
 }
 
-fn sendmsg(stream: &UnixStream, bytes: &[u8], fds: VecDeque<RawFd>) {
-    let fds: Vec<_> = fds
-        .iter()
-        .map(|fd| unsafe { BorrowedFd::borrow_raw(*fd) })
-        .collect();
+fn sendmsg(stream: &UnixStream, bytes: &[u8], fds: VecDeque<OwnedFd>) {
+    let fds: Vec<_> = fds.iter().map(AsFd::as_fd).collect();
     let iov = [IoSlice::new(bytes)];
 
     let mut space = vec![MaybeUninit::uninit(); rustix::cmsg_space!(ScmRights(fds.len()))];
@@ -66,32 +65,45 @@ fn sendmsg(stream: &UnixStream, bytes: &[u8], fds: VecDeque<RawFd>) {
 }
 
 fn main() {
-    std::fs::remove_file("/run/user/1000/pipewire-1").ok();
-    let listener = UnixListener::bind("/run/user/1000/pipewire-1").unwrap();
+    let runtime_dir = ripewire::connection::runtime_dir().expect("no runtime dir");
+    let proxy_path = runtime_dir.join("pipewire-1");
+
+    std::fs::remove_file(&proxy_path).ok();
+    let listener = UnixListener::bind(&proxy_path).unwrap();
 
-    let server = Arc::new(UnixStream::connect("/run/user/1000/pipewire-0").unwrap());
+    let server = Arc::new(UnixStream::connect(runtime_dir.join("pipewire-0")).unwrap());
 
     let (stream, _add) = listener.accept().unwrap();
     let client = Arc::new(stream);
 
     let objects = Arc::new(Mutex::new(objects()));
+    // Negotiated by the Hello of the client, the daemon answers in the same format
+    let version = Arc::new(Mutex::new(None::<ProtocolVersion>));
     let client_in = std::thread::spawn({
         let client = client.clone();
         let server = server.clone();
         let mut buffer = vec![0u8; MAX_BUFFER_SIZE];
         let objects = objects.clone();
+        let version = version.clone();
         let interfaces = interfaces();
+        let mut format = PeerFormat::default();
         move || loop {
             buffer.fill(0);
             let (bytes, fds) = recvmsg(&client, &mut buffer);
 
-            let mut reader = bytes;
-            let mut fds_read = fds.clone();
-            while let Some((rest, msg)) = ripewire::connection::read_msg(reader, &mut fds_read) {
+            // Inspected messages take ownership of their fds and may get their pods converted,
+            // so they work on a copy, the originals get forwarded
+            let mut inspected = bytes.to_vec();
+            let mut reader = inspected.as_mut_slice();
+            let mut fds_read = fds.iter().map(|fd| fd.try_clone().unwrap()).collect();
+            while let Some((rest, msg)) =
+                ripewire::connection::read_msg(reader, &mut fds_read, &mut format).unwrap()
+            {
                 reader = rest;
                 inspect_method(&objects, &interfaces, &msg);
                 // pod::dbg_print::dbg_print(&msg.body);
             }
+            *version.lock().unwrap() = format.version;
 
             sendmsg(&server, bytes, fds);
         }
@@ -102,16 +114,24 @@ fn main() {
         let server = server.clone();
         let mut buffer = vec![0u8; MAX_BUFFER_SIZE];
         let objects = objects.clone();
+        let version = version.clone();
         let interfaces = interfaces();
+        let mut format = PeerFormat::default();
         move || loop {
             buffer.fill(0);
             let (bytes, fds) = recvmsg(&server, &mut buffer);
 
-            let mut reader = bytes;
-            let mut fds_read = fds.clone();
-            while let Some((rest, msg)) = ripewire::connection::read_msg(reader, &mut fds_read) {
+            // Inspected messages take ownership of their fds and may get their pods converted,
+            // so they work on a copy, the originals get forwarded
+            let mut inspected = bytes.to_vec();
+            let mut reader = inspected.as_mut_slice();
+            let mut fds_read = fds.iter().map(|fd| fd.try_clone().unwrap()).collect();
+            format.version = *version.lock().unwrap();
+            while let Some((rest, mut msg)) =
+                ripewire::connection::read_msg(reader, &mut fds_read, &mut format).unwrap()
+            {
                 reader = rest;
-                inspect_event(&objects, &interfaces, &msg);
+                inspect_event(&objects, &interfaces, &mut msg);
                 // pod::dbg_print::dbg_print(&msg.body);
             }
 
@@ -219,7 +239,7 @@ fn inspect_method(objects: &Mutex<Objects>, interfaces: &Interfaces, msg: &Messa
     }
 }
 
-fn inspect_event(objects: &Mutex<Objects>, interfaces: &Interfaces, msg: &Message) {
+fn inspect_event(objects: &Mutex<Objects>, interfaces: &Interfaces, msg: &mut Message) {
     let objects = objects.lock().unwrap();
 
     print!("-> ");
@@ -253,10 +273,10 @@ fn inspect_event(objects: &Mutex<Objects>, interfaces: &Interfaces, msg: &Messag
     }
 }
 
-fn inspect_core_event(opcode: u8, msg: &Message) {
+fn inspect_core_event(opcode: u8, msg: &mut Message) {
     use ripewire::protocol::pw_core::Event;
     let mut pod = msg.body.clone();
-    match Event::deserialize(opcode, &mut pod, &msg.fds).unwrap() {
+    match Event::deserialize(opcode, &mut pod, &mut msg.fds).unwrap() {
         Event::Info(v) => println!("{v:#?}"),
         Event::Done(v) => println!("{v:?}"),
         Event::Ping(v) => println!("{v:?}"),
@@ -269,19 +289,20 @@ fn inspect_core_event(opcode: u8, msg: &Message) {
     }
 }
 
-fn inspect_client_event(opcode: u8, msg: &Message) {
+fn inspect_client_event(opcode: u8, msg: &mut Message) {
     use ripewire::protocol::pw_client::Event;
     let mut pod = msg.body.clone();
-    match Event::deserialize(opcode, &mut pod, &msg.fds).unwrap() {
+    match Event::deserialize(opcode, &mut pod, &mut msg.fds).unwrap() {
         Event::Info(v) => println!("{v:#?}"),
         Event::Permissions(v) => println!("{v:#?}"),
+        Event::Removed => {}
     }
 }
 
-fn inspect_client_node_event(opcode: u8, msg: &Message) {
+fn inspect_client_node_event(opcode: u8, msg: &mut Message) {
     use ripewire::protocol::pw_client_node::Event;
     let mut pod = msg.body.clone();
-    match Event::deserialize(opcode, &mut pod, &msg.fds).unwrap() {
+    match Event::deserialize(opcode, &mut pod, &mut msg.fds).unwrap() {
         Event::Transport(v) => println!("{v:#?}"),
         Event::SetParam(v) => println!("{v:#?}"),
         Event::SetIo(v) => println!("{v:#?}"),
@@ -294,66 +315,74 @@ fn inspect_client_node_event(opcode: u8, msg: &Message) {
         Event::PortSetIo(v) => println!("{v:#?}"),
         Event::SetActivation(v) => println!("{v:#?}"),
         Event::PortSetMixInfo(v) => println!("{v:#?}"),
+        Event::Removed => {}
     }
 }
 
-fn inspect_device_event(opcode: u8, msg: &Message) {
+fn inspect_device_event(opcode: u8, msg: &mut Message) {
     use ripewire::protocol::pw_device::Event;
     let mut pod = msg.body.clone();
-    match Event::deserialize(opcode, &mut pod, &msg.fds).unwrap() {
+    match Event::deserialize(opcode, &mut pod, &mut msg.fds).unwrap() {
         Event::Info(v) => println!("{v:#?}"),
         Event::Param(v) => println!("{v:#?}"),
+        Event::Removed => {}
     }
 }
 
-fn inspect_factory_event(opcode: u8, msg: &Message) {
+fn inspect_factory_event(opcode: u8, msg: &mut Message) {
     use ripewire::protocol::pw_factory::Event;
     let mut pod = msg.body.clone();
-    match Event::deserialize(opcode, &mut pod, &msg.fds).unwrap() {
+    match Event::deserialize(opcode, &mut pod, &mut msg.fds).unwrap() {
         Event::Info(v) => println!("{v:#?}"),
+        Event::Removed => {}
     }
 }
 
-fn inspect_link_event(opcode: u8, msg: &Message) {
+fn inspect_link_event(opcode: u8, msg: &mut Message) {
     use ripewire::protocol::pw_link::Event;
     let mut pod = msg.body.clone();
-    match Event::deserialize(opcode, &mut pod, &msg.fds).unwrap() {
+    match Event::deserialize(opcode, &mut pod, &mut msg.fds).unwrap() {
         Event::Info(v) => println!("{v:#?}"),
+        Event::Removed => {}
     }
 }
 
-fn inspect_module_event(opcode: u8, msg: &Message) {
+fn inspect_module_event(opcode: u8, msg: &mut Message) {
     use ripewire::protocol::pw_module::Event;
     let mut pod = msg.body.clone();
-    match Event::deserialize(opcode, &mut pod, &msg.fds).unwrap() {
+    match Event::deserialize(opcode, &mut pod, &mut msg.fds).unwrap() {
         Event::Info(v) => println!("{v:#?}"),
+        Event::Removed => {}
     }
 }
 
-fn inspect_node_event(opcode: u8, msg: &Message) {
+fn inspect_node_event(opcode: u8, msg: &mut Message) {
     use ripewire::protocol::pw_node::Event;
     let mut pod = msg.body.clone();
-    match Event::deserialize(opcode, &mut pod, &msg.fds).unwrap() {
+    match Event::deserialize(opcode, &mut pod, &mut msg.fds).unwrap() {
         Event::Info(v) => println!("{v:#?}"),
         Event::Param(v) => println!("{v:#?}"),
+        Event::Removed => {}
     }
 }
 
-fn inspect_port_event(opcode: u8, msg: &Message) {
+fn inspect_port_event(opcode: u8, msg: &mut Message) {
     use ripewire::protocol::pw_port::Event;
     let mut pod = msg.body.clone();
-    match Event::deserialize(opcode, &mut pod, &msg.fds).unwrap() {
+    match Event::deserialize(opcode, &mut pod, &mut msg.fds).unwrap() {
         Event::Info(v) => println!("{v:#?}"),
         Event::Param(v) => println!("{v:#?}"),
+        Event::Removed => {}
     }
 }
 
-fn inspect_registry_event(opcode: u8, msg: &Message) {
+fn inspect_registry_event(opcode: u8, msg: &mut Message) {
     use ripewire::protocol::pw_registry::Event;
     let mut pod = msg.body.clone();
-    match Event::deserialize(opcode, &mut pod, &msg.fds).unwrap() {
+    match Event::deserialize(opcode, &mut pod, &mut msg.fds).unwrap() {
         Event::Global(v) => println!("{v:#?}"),
         Event::GlobalRemove(v) => println!("{v:?}"),
+        Event::Removed => {}
     }
 }
 
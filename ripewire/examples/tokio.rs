@@ -1,10 +1,8 @@
 use libspa_consts::{SpaParamRoute, SpaParamType, SpaProp, SpaType};
 use ripewire::connection::MessageBuffer;
-use ripewire::memory_registry::MemoryRegistry;
 use ripewire::object_map::ObjectType;
 use ripewire::HashMapExt;
 use std::collections::HashMap;
-use std::io;
 use std::os::fd::AsRawFd;
 use tokio::io::unix::AsyncFd;
 
@@ -16,34 +14,21 @@ use ripewire::proxy::{PwClient, PwCore, PwDevice, PwRegistry};
 struct PipewireState {
     registry: PwRegistry,
     globals: GlobalList,
-    mems: MemoryRegistry,
 }
 
 impl PipewireState {
     pub fn core_event(
         &mut self,
         context: &mut Context<Self>,
-        core: PwCore,
+        _core: PwCore,
         core_event: pw_core::Event,
     ) {
         dbg!(&core_event);
 
-        match core_event {
-            pw_core::Event::Done(done) => {
-                if done.id == Some(0) && done.seq == 0 {
-                    self.done(context);
-                }
-            }
-            pw_core::Event::AddMem(add_mem) => {
-                self.mems.add_mem(&add_mem);
-            }
-            pw_core::Event::RemoveMem(remove_mem) => {
-                self.mems.remove_mem(&remove_mem);
+        if let pw_core::Event::Done(done) = core_event {
+            if done.id == Some(0) && done.seq == 0 {
+                self.done(context);
             }
-            pw_core::Event::Ping(ping) => {
-                core.pong(context, ping.id, ping.seq);
-            }
-            _ => {}
         }
     }
 
@@ -88,38 +73,44 @@ impl PipewireState {
         let Some(global) = device else {
             return;
         };
-        let device: PwDevice = self.registry.bind(context, global);
+        let device: PwDevice = self.registry.bind_untyped(context, global).unwrap();
 
         context.set_object_callback(&device, Self::device_event);
 
-        device.set_param(
-            context,
-            // TODO: Not very fun to write
-            pod::Builder::with(|b| {
-                b.write_object_with(SpaType::ObjectParamRoute, SpaParamType::Route as u32, |b| {
-                    b.write_property(SpaParamRoute::Index as u32, 0, |b| {
-                        b.write_u32(4);
-                    });
-                    b.write_property(SpaParamRoute::Device as u32, 0, |b| {
-                        b.write_u32(4);
-                    });
-                    b.write_property(SpaParamRoute::Props as u32, 0, |b| {
-                        b.write_object_with(
-                            SpaType::ObjectProps,
-                            SpaParamType::Route as u32,
-                            |b| {
-                                b.write_property(SpaProp::Mute as u32, 0, |b| {
-                                    b.write_bool(false);
-                                });
-                                b.write_property(SpaProp::Volume as u32, 0, |b| {
-                                    b.write_float(0.1);
-                                });
-                            },
-                        );
-                    });
-                });
-            }),
-        );
+        device
+            .set_param(
+                context,
+                // TODO: Not very fun to write
+                pod::Builder::with(|b| {
+                    b.write_object_with(
+                        SpaType::ObjectParamRoute,
+                        SpaParamType::Route as u32,
+                        |b| {
+                            b.write_property(SpaParamRoute::Index as u32, 0, |b| {
+                                b.write_u32(4);
+                            });
+                            b.write_property(SpaParamRoute::Device as u32, 0, |b| {
+                                b.write_u32(4);
+                            });
+                            b.write_property(SpaParamRoute::Props as u32, 0, |b| {
+                                b.write_object_with(
+                                    SpaType::ObjectProps,
+                                    SpaParamType::Route as u32,
+                                    |b| {
+                                        b.write_property(SpaProp::Mute as u32, 0, |b| {
+                                            b.write_bool(false);
+                                        });
+                                        b.write_property(SpaProp::Volume as u32, 0, |b| {
+                                            b.write_float(0.1);
+                                        });
+                                    },
+                                );
+                            });
+                        },
+                    );
+                }),
+            )
+            .unwrap();
     }
 }
 
@@ -130,23 +121,25 @@ struct TokioState {
 
 #[tokio::main]
 async fn main() {
-    let mut ctx = Context::<PipewireState>::connect("/run/user/1000/pipewire-0").unwrap();
+    let mut ctx = Context::<PipewireState>::connect_default().unwrap();
     let core = ctx.core();
     let client = ctx.client();
 
-    ctx.core().hello(&mut ctx);
+    ctx.core().hello(&mut ctx).unwrap();
 
-    ctx.client().update_properties(
-        &mut ctx,
-        PwDictionary::from_dict([
-            ("application.name", "ripewire"),
-            ("application.process.binary", "ripewire"),
-        ]),
-    );
+    ctx.client()
+        .update_properties(
+            &mut ctx,
+            PwDictionary::from_dict([
+                ("application.name", "ripewire"),
+                ("application.process.binary", "ripewire"),
+            ]),
+        )
+        .unwrap();
 
-    let registry = ctx.core().get_registry(&mut ctx);
+    let registry = ctx.core().get_registry(&mut ctx).unwrap();
 
-    core.sync(&mut ctx, 0, 0);
+    core.sync(&mut ctx, 0, 0).unwrap();
 
     ctx.set_object_callback(&core, PipewireState::core_event);
     ctx.set_object_callback(&client, PipewireState::client_event);
@@ -159,19 +152,29 @@ async fn main() {
         state: PipewireState {
             registry,
             globals: GlobalList::default(),
-            mems: MemoryRegistry::default(),
         },
     };
 
     let mut buffer = MessageBuffer::new();
     loop {
+        match state.ctx.flush() {
+            Ok(()) => {}
+            Err(err) if err.is_would_block() => {
+                fd.writable().await.unwrap().clear_ready();
+                continue;
+            }
+            Err(err) => {
+                panic!("{err}");
+            }
+        }
+
         let fd = fd.readable().await.unwrap();
 
         if fd.ready().is_readable() {
             loop {
-                let msg = match state.ctx.rcv_msg(&mut buffer) {
+                let messages = match state.ctx.rcv_msg(&mut buffer) {
                     Ok(res) => res,
-                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    Err(err) if err.is_would_block() => {
                         break;
                     }
                     Err(err) => {
@@ -179,7 +182,12 @@ async fn main() {
                     }
                 };
 
-                state.ctx.dispatch_event(&mut state.state, msg);
+                for msg in messages {
+                    state
+                        .ctx
+                        .dispatch_event(&mut state.state, msg.unwrap())
+                        .unwrap();
+                }
             }
         }
     }
@@ -3,20 +3,179 @@ use std::{
     io::{self, IoSlice, IoSliceMut},
     mem::{self, MaybeUninit},
     os::{
-        fd::{AsRawFd, BorrowedFd, IntoRawFd, RawFd},
+        fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
         unix::net::UnixStream,
     },
-    path::Path,
+    path::{Path, PathBuf},
 };
 
-use pod::PodDeserializer;
+use libspa_consts::SpaType;
+use pod::{deserialize::PodStructDeserializer, PodDeserializer};
 use rustix::net::{
     RecvAncillaryBuffer, RecvAncillaryMessage, RecvFlags, SendAncillaryBuffer,
     SendAncillaryMessage, SendFlags,
 };
 
+use crate::{type_map::TypeMap, Error, Result};
+
 pub const MAX_FDS_OUT: usize = 28;
 
+/// Name of the socket the daemon listens on
+pub const DEFAULT_REMOTE: &str = "pipewire-0";
+/// Name of the socket with manager permissions, used by session managers and similar tools
+pub const DEFAULT_MANAGER_REMOTE: &str = "pipewire-0-manager";
+/// Runtime directory used by system-wide daemon instances
+pub const SYSTEM_RUNTIME_DIR: &str = "/run/pipewire";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConnectError {
+    /// `PIPEWIRE_REMOTE` contained a file descriptor that is not usable
+    #[error("invalid fd {fd} passed in PIPEWIRE_REMOTE: {error}")]
+    InvalidFd { fd: RawFd, error: io::Error },
+    #[error("failed to connect to PipeWire daemon, tried: {}", display_tried(.tried))]
+    NoSocket {
+        /// Every socket path that was tried, in order, together with the reason it failed
+        tried: Vec<(PathBuf, io::Error)>,
+    },
+}
+
+fn display_tried(tried: &[(PathBuf, io::Error)]) -> String {
+    if tried.is_empty() {
+        return "nothing".into();
+    }
+
+    tried
+        .iter()
+        .map(|(path, err)| format!("{} ({err})", path.display()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// File descriptor passed in `PIPEWIRE_REMOTE`, if the variable holds a number
+///
+/// The variable is removed once the fd got taken over, the same way libpipewire does it, so that
+/// later connections and child processes don't try to use the fd again.
+fn remote_fd() -> Result<Option<OwnedFd>, ConnectError> {
+    let Some(fd) = std::env::var("PIPEWIRE_REMOTE")
+        .ok()
+        .and_then(|remote| remote.trim().parse::<RawFd>().ok())
+    else {
+        return Ok(None);
+    };
+
+    if fd < 0 || unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        let error = if fd < 0 {
+            io::Error::from_raw_os_error(libc::EBADF)
+        } else {
+            io::Error::last_os_error()
+        };
+        return Err(ConnectError::InvalidFd { fd, error });
+    }
+
+    std::env::remove_var("PIPEWIRE_REMOTE");
+
+    // SAFETY: The fd was handed to us by the parent process and is open, with the variable
+    // removed nobody else in this process takes it over.
+    Ok(Some(unsafe { OwnedFd::from_raw_fd(fd) }))
+}
+
+/// List of remote names to try, in order
+///
+/// Mirrors libpipewire: `PIPEWIRE_REMOTE` takes precedence, otherwise the default name is used.
+/// The value may contain multiple comma separated names, optionally wrapped in a SPA-JSON array,
+/// eg. `[ pipewire-0-manager, pipewire-0 ]`. Names are only split on commas, paths may contain
+/// spaces.
+pub fn remote_names(manager: bool) -> Vec<String> {
+    let remote = std::env::var("PIPEWIRE_REMOTE")
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| {
+            if manager {
+                format!("{DEFAULT_MANAGER_REMOTE},{DEFAULT_REMOTE}")
+            } else {
+                DEFAULT_REMOTE.to_string()
+            }
+        });
+
+    remote
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|name| name.trim().trim_matches('"'))
+        .filter(|name| !name.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Runtime directory of the current user
+///
+/// `PIPEWIRE_RUNTIME_DIR`, then `XDG_RUNTIME_DIR`, then `USERPROFILE`, same as libpipewire.
+pub fn runtime_dir() -> Option<PathBuf> {
+    ["PIPEWIRE_RUNTIME_DIR", "XDG_RUNTIME_DIR", "USERPROFILE"]
+        .into_iter()
+        .find_map(std::env::var_os)
+        .map(PathBuf::from)
+}
+
+/// Socket paths to try for the given remote names, in order
+///
+/// Absolute names are used as is, relative names are looked up in the user runtime dir first and
+/// in the system runtime dir second.
+pub fn socket_candidates(names: &[String]) -> Vec<PathBuf> {
+    let runtime_dir = runtime_dir();
+
+    let mut candidates = Vec::new();
+    for name in names {
+        let name = Path::new(name);
+
+        if name.is_absolute() {
+            candidates.push(name.to_path_buf());
+            continue;
+        }
+
+        if let Some(runtime_dir) = runtime_dir.as_ref() {
+            candidates.push(runtime_dir.join(name));
+        }
+        candidates.push(Path::new(SYSTEM_RUNTIME_DIR).join(name));
+    }
+
+    candidates
+}
+
+/// Version of the native protocol message format, negotiated per connection
+///
+/// Clients older than PipeWire 0.3 use the v0 format, the daemon recognizes them by the
+/// layout of their first `Hello` message and uses the old format for the whole connection,
+/// see [`read_header`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProtocolVersion {
+    /// 8 byte header without `seq` and `n_fds`, pods use the v0 type ids and the type map
+    /// of the peer, see [`TypeMap`]
+    V0,
+    /// 16 byte header
+    #[default]
+    V3,
+}
+
+impl ProtocolVersion {
+    pub const fn header_size(self) -> usize {
+        match self {
+            Self::V0 => 8,
+            Self::V3 => 16,
+        }
+    }
+}
+
+/// Format of the messages read from a peer, see [`read_msg`]
+#[derive(Debug, Clone, Default)]
+pub struct PeerFormat {
+    /// `None` until the first message was read, see [`read_header`]
+    pub version: Option<ProtocolVersion>,
+    /// Types registered by a v0 peer
+    pub types: TypeMap,
+}
+
 #[derive(Debug, Clone)]
 pub struct Header {
     pub object_id: u32,
@@ -50,6 +209,13 @@ impl Header {
         }
     }
 
+    /// Deserialize the v0 header, it has no `seq` and `n_fds`, both are set to 0
+    ///
+    /// The fds of v0 messages are not counted, see [`read_msg`].
+    pub fn deserialize_v0(bytes: &[u32]) -> Self {
+        Self::deserialize(&[bytes[0], bytes[1], 0, 0])
+    }
+
     pub fn serialize(&self) -> [u8; 16] {
         let mut buffer = [0; 16 / mem::size_of::<u32>()];
         let opcode = self.opcode as u32;
@@ -61,23 +227,137 @@ impl Header {
 
         unsafe { mem::transmute(buffer) }
     }
+
+    /// Serialize the v0 header, `seq` and `n_fds` are not part of it
+    pub fn serialize_v0(&self) -> [u8; 8] {
+        let mut buffer = [0; 8];
+        buffer.copy_from_slice(&self.serialize()[..8]);
+        buffer
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Message<'a> {
     pub header: Header,
     pub body: PodDeserializer<'a>,
     pub footer: Option<PodDeserializer<'a>>,
-    pub fds: Vec<RawFd>,
+    /// Fds sent along with the message, deserialized events take the ones they reference
+    ///
+    /// Fds that nobody took are closed when the message is dropped.
+    pub fds: Vec<Option<OwnedFd>>,
+}
+
+impl Message<'_> {
+    /// Parse the footer sent by the daemon, empty if the message has none
+    pub fn core_footers(&self) -> pod::deserialize::Result<Vec<FooterCore>> {
+        match &self.footer {
+            Some(footer) => FooterCore::parse(footer),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Parse the footer sent by a client, empty if the message has none
+    pub fn client_footers(&self) -> pod::deserialize::Result<Vec<FooterClient>> {
+        match &self.footer {
+            Some(footer) => FooterClient::parse(footer),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Footer opcodes sent by the daemon, after the body of any message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FooterCore {
+    /// Current registry generation, it increases every time a global is added or removed
+    Generation(u64),
 }
 
+impl FooterCore {
+    pub const GENERATION: u32 = 0;
+
+    /// Parse footer opcodes, the ones that are not known are skipped
+    pub fn parse(footer: &PodDeserializer) -> pod::deserialize::Result<Vec<Self>> {
+        parse_footers(footer, |opcode, args| match opcode {
+            Self::GENERATION => Ok(Some(Self::Generation(args.pop_field()?.as_u64()?))),
+            _ => Ok(None),
+        })
+    }
+}
+
+/// Footer opcodes sent by the client, after the body of any message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FooterClient {
+    /// Last registry generation seen by the client
+    ///
+    /// Lets the daemon know if a global the client refers to was removed and re-added since.
+    Generation(u64),
+}
+
+impl FooterClient {
+    pub const GENERATION: u32 = 0;
+
+    /// Parse footer opcodes, the ones that are not known are skipped
+    pub fn parse(footer: &PodDeserializer) -> pod::deserialize::Result<Vec<Self>> {
+        parse_footers(footer, |opcode, args| match opcode {
+            Self::GENERATION => Ok(Some(Self::Generation(args.pop_field()?.as_u64()?))),
+            _ => Ok(None),
+        })
+    }
+
+    /// Serialize the footer pod, appended to the message right after the body
+    pub fn serialize(footers: &[Self]) -> Vec<u8> {
+        let mut buff = io::Cursor::new(Vec::new());
+        pod::Builder::new(&mut buff).push_struct_with(|b| {
+            for footer in footers {
+                match footer {
+                    Self::Generation(generation) => {
+                        b.write_id(Self::GENERATION);
+                        b.push_struct_with(|b| {
+                            b.write_u64(*generation);
+                        });
+                    }
+                }
+            }
+        });
+        buff.into_inner()
+    }
+}
+
+/// Footer is a struct of `(Id opcode, Struct args)` pairs
+fn parse_footers<T>(
+    footer: &PodDeserializer,
+    mut parse: impl FnMut(u32, &mut PodStructDeserializer) -> pod::deserialize::Result<Option<T>>,
+) -> pod::deserialize::Result<Vec<T>> {
+    let mut footer = footer.as_struct()?;
+    let mut footers = Vec::new();
+
+    while let Some(opcode) = footer.next() {
+        let opcode = opcode.as_id()?;
+        let mut args = footer.pop_field()?.as_struct()?;
+        footers.extend(parse(opcode, &mut args)?);
+    }
+
+    Ok(footers)
+}
+
+/// Initial size of the [`MessageBuffer`]
+const INITIAL_BUFFER_SIZE: usize = 16 * 1024;
+/// Minimal amount of free space in [`MessageBuffer`] before reading from the socket,
+/// the buffer gets compacted or grown if there is less space left
+const MIN_READ_SIZE: usize = 4096;
+
+/// Buffer for incoming messages
+///
+/// Every read takes as much data as the socket has and fits in the buffer, messages are then
+/// deserialized in place, without copying. Incomplete messages are kept in the buffer until the
+/// rest of the data arrives. The buffer only gets compacted, or grown in case of really big
+/// messages, when there is not enough space left for the next read.
 pub struct MessageBuffer {
     buffer: Vec<u8>,
-    fds: VecDeque<RawFd>,
+    fds: VecDeque<OwnedFd>,
     start: usize,
     end: usize,
-
-    staging_buffer: Vec<u8>,
+    format: PeerFormat,
 }
 
 impl Default for MessageBuffer {
@@ -89,21 +369,206 @@ impl Default for MessageBuffer {
 impl MessageBuffer {
     pub fn new() -> Self {
         Self {
-            buffer: vec![0u8; 4096],
+            buffer: vec![0u8; INITIAL_BUFFER_SIZE],
             fds: VecDeque::new(),
             start: 0,
             end: 0,
-            staging_buffer: Vec::new(),
+            format: PeerFormat::default(),
         }
     }
 
-    fn len(&self) -> usize {
-        self.end - self.start
+    /// Message format used by the peer, see [`ProtocolVersion`]
+    pub fn version(&self) -> ProtocolVersion {
+        self.format.version.unwrap_or_default()
+    }
+
+    /// Types registered by a v0 peer, see [`ProtocolVersion::V0`]
+    pub fn type_map(&self) -> &TypeMap {
+        &self.format.types
+    }
+
+    /// Does the buffer hold at least one complete message
+    pub fn has_message(&self) -> bool {
+        let mut version = self.format.version;
+        read_header(&self.buffer[self.start..self.end], &mut version)
+            .is_some_and(|(body, header)| body.len() >= header.len as usize)
+    }
+
+    /// Iterate over complete messages that are already in the buffer
+    ///
+    /// Every message yielded by the iterator is consumed, messages that were not yet
+    /// yielded when the iterator gets dropped stay in the buffer. A message that fails
+    /// to parse is consumed as well, its error is yielded in its place.
+    pub fn messages(&mut self) -> Messages<'_> {
+        Messages {
+            buffer: &mut self.buffer[self.start..self.end],
+            start: &mut self.start,
+            fds: &mut self.fds,
+            format: &mut self.format,
+        }
+    }
+
+    fn make_room(&mut self) {
+        if self.start == self.end {
+            self.start = 0;
+            self.end = 0;
+        }
+
+        if self.buffer.len() - self.end >= MIN_READ_SIZE {
+            return;
+        }
+
+        // Move the incomplete message to the front
+        self.buffer.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+
+        if self.buffer.len() - self.end < MIN_READ_SIZE {
+            self.buffer.resize(self.buffer.len() * 2, 0);
+        }
+    }
+
+    /// Read as much as the socket has to offer
+    fn read_from(&mut self, stream: &UnixStream) -> io::Result<usize> {
+        self.make_room();
+
+        let mut space = [MaybeUninit::uninit(); rustix::cmsg_space!(ScmRights(MAX_FDS_OUT))];
+        let mut cmsgs = RecvAncillaryBuffer::new(&mut space);
+
+        let mut iov = [IoSliceMut::new(&mut self.buffer[self.end..])];
+
+        let msg = rustix::net::recvmsg(stream, &mut iov, &mut cmsgs, RecvFlags::CMSG_CLOEXEC)?;
+
+        let received_fds = cmsgs
+            .drain()
+            .filter_map(|cmsg| match cmsg {
+                RecvAncillaryMessage::ScmRights(fds) => Some(fds),
+                _ => None,
+            })
+            .flatten();
+        self.fds.extend(received_fds);
+
+        if msg.bytes == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        self.end += msg.bytes;
+
+        Ok(msg.bytes)
+    }
+}
+
+/// Iterator over complete messages in a [`MessageBuffer`]
+pub struct Messages<'a> {
+    /// Messages that were not yielded yet
+    buffer: &'a mut [u8],
+    start: &'a mut usize,
+    fds: &'a mut VecDeque<OwnedFd>,
+    format: &'a mut PeerFormat,
+}
+
+impl<'a> Iterator for Messages<'a> {
+    type Item = Result<Message<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let buffer = mem::take(&mut self.buffer);
+
+        let mut version = self.format.version;
+        let len = match read_header(buffer, &mut version) {
+            Some((body, header)) if body.len() >= header.len as usize => {
+                buffer.len() - body.len() + header.len as usize
+            }
+            _ => {
+                self.buffer = buffer;
+                return None;
+            }
+        };
+
+        // The message is consumed even if it turns out to be broken,
+        // otherwise it would stay at the head of the buffer forever
+        let (msg, rest) = buffer.split_at_mut(len);
+        *self.start += len;
+        self.buffer = rest;
+
+        read_msg(msg, self.fds, self.format)
+            .transpose()
+            .map(|msg| msg.map(|(_, msg)| msg))
+    }
+}
+
+/// Serialized messages waiting to be written to the socket
+///
+/// Messages are coalesced into batches, every batch is written with a single `sendmsg` call
+/// and carries at most [`MAX_FDS_OUT`] fds.
+#[derive(Debug, Default)]
+struct OutBuffer {
+    batches: VecDeque<OutBatch>,
+}
+
+#[derive(Debug, Default)]
+struct OutBatch {
+    bytes: Vec<u8>,
+    fds: Vec<OwnedFd>,
+}
+
+impl OutBuffer {
+    /// Returns the queued copy of the message
+    fn push(&mut self, bytes: &[u8], fds: &[BorrowedFd]) -> io::Result<&mut [u8]> {
+        if fds.len() > MAX_FDS_OUT {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("message carries {} fds, max is {MAX_FDS_OUT}", fds.len()),
+            ));
+        }
+
+        // The fds have to stay valid until the batch gets flushed, so we keep our own copy
+        let fds = fds
+            .iter()
+            .map(|fd| fd.try_clone_to_owned())
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let fits = self
+            .batches
+            .back()
+            .is_some_and(|batch| batch.fds.len() + fds.len() <= MAX_FDS_OUT);
+        if !fits {
+            self.batches.push_back(OutBatch::default());
+        }
+        let batch = self.batches.back_mut().unwrap();
+
+        let start = batch.bytes.len();
+        batch.bytes.extend_from_slice(bytes);
+        batch.fds.extend(fds);
+
+        Ok(&mut batch.bytes[start..])
+    }
+
+    fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    fn flush(&mut self, stream: &UnixStream) -> io::Result<()> {
+        while let Some(batch) = self.batches.front_mut() {
+            let fds: Vec<_> = batch.fds.iter().map(AsFd::as_fd).collect();
+            let written = send_msg(stream, &batch.bytes, &fds)?;
+
+            // Fds are sent along with the first chunk of the batch,
+            // so they are available before any message referencing them arrives
+            batch.fds.clear();
+            batch.bytes.drain(..written);
+
+            if batch.bytes.is_empty() {
+                self.batches.pop_front();
+            }
+        }
+
+        Ok(())
     }
 }
 
 pub struct Connection {
     stream: UnixStream,
+    out: OutBuffer,
 }
 
 impl Connection {
@@ -111,16 +576,104 @@ impl Connection {
         UnixStream::connect(path).map(Self::from_stream)
     }
 
+    /// Connect to the daemon socket resolved from the environment, see [`remote_names`]
+    ///
+    /// If `PIPEWIRE_REMOTE` holds a file descriptor number, that fd is used instead. The variable
+    /// gets removed then, the fd can only be used by a single connection.
+    pub fn connect_default() -> Result<Self, ConnectError> {
+        if let Some(fd) = remote_fd()? {
+            return Ok(Self::from_fd(fd));
+        }
+        Self::connect_first(&socket_candidates(&remote_names(false)))
+    }
+
+    /// Same as [`Connection::connect_default`] but prefers the manager socket
+    pub fn connect_default_manager() -> Result<Self, ConnectError> {
+        if let Some(fd) = remote_fd()? {
+            return Ok(Self::from_fd(fd));
+        }
+        Self::connect_first(&socket_candidates(&remote_names(true)))
+    }
+
+    /// Connect to the first path that accepts the connection
+    pub fn connect_first(paths: &[PathBuf]) -> Result<Self, ConnectError> {
+        let mut tried = Vec::new();
+
+        for path in paths {
+            match Self::connect(path) {
+                Ok(this) => return Ok(this),
+                Err(err) => tried.push((path.clone(), err)),
+            }
+        }
+
+        Err(ConnectError::NoSocket { tried })
+    }
+
+    /// Use an already connected socket, eg. one inherited from a parent process or a portal
+    pub fn from_fd(fd: OwnedFd) -> Self {
+        Self::from_stream(UnixStream::from(fd))
+    }
+
     pub fn from_stream(stream: UnixStream) -> Self {
-        Self { stream }
+        Self {
+            stream,
+            out: OutBuffer::default(),
+        }
+    }
+
+    /// Queue a serialized message, it will be written to the socket on next [`Connection::flush`]
+    ///
+    /// The fds are duplicated, so the caller is free to close them right away.
+    pub fn send_msg(&mut self, bytes: &[u8], fds: &[BorrowedFd]) -> io::Result<()> {
+        self.out.push(bytes, fds)?;
+        Ok(())
+    }
+
+    /// Same as [`Connection::send_msg`], with `seq` written into the message header
+    pub fn send_msg_with_seq(
+        &mut self,
+        bytes: &[u8],
+        fds: &[BorrowedFd],
+        seq: u32,
+    ) -> io::Result<()> {
+        let msg = self.out.push(bytes, fds)?;
+        if let Some(field) = msg.get_mut(8..12) {
+            field.copy_from_slice(&seq.to_ne_bytes());
+        }
+        Ok(())
+    }
+
+    /// Write all queued messages to the socket
+    ///
+    /// Returns [`io::ErrorKind::WouldBlock`] if the socket is not able to take all of the data
+    /// right now, in which case one should wait for the socket to become writable and try again.
+    /// Messages that were not written yet stay in the queue.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush(&self.stream)
     }
 
-    pub fn send_msg(&mut self, bytes: &[u8], fds: &[RawFd]) -> io::Result<usize> {
-        send_msg(&self.stream, bytes, fds)
+    /// Are there any messages waiting to be flushed
+    pub fn has_pending_writes(&self) -> bool {
+        !self.out.is_empty()
+    }
+
+    /// Receive messages from the socket
+    ///
+    /// Reads from the socket only if there is no complete message in the buffer already,
+    /// the returned iterator can be empty if only a part of a message was received so far.
+    ///
+    /// Returns [`Error::Disconnected`] once the daemon closes the socket.
+    pub fn rcv_msg<'a>(&mut self, buffer: &'a mut MessageBuffer) -> Result<Messages<'a>> {
+        if !buffer.has_message() {
+            buffer.read_from(&self.stream)?;
+        }
+
+        Ok(buffer.messages())
     }
 
-    pub fn rcv_msg<'a>(&mut self, buffer: &'a mut MessageBuffer) -> io::Result<Message<'a>> {
-        rcv_msg(&self.stream, buffer)
+    /// Read from the socket into `buffer`, no matter if it has complete messages already
+    pub fn read(&mut self, buffer: &mut MessageBuffer) -> Result<usize> {
+        Ok(buffer.read_from(&self.stream)?)
     }
 }
 
@@ -130,81 +683,231 @@ impl AsRawFd for Connection {
     }
 }
 
-pub fn read_header(buff: &[u8]) -> Option<(&[u8], Header)> {
-    const HDR_SIZE: usize = 16;
-    if buff.len() < 4 * mem::size_of::<u32>() && buff.len() < HDR_SIZE {
-        return None;
-    }
-
-    let header = {
-        let buff = unsafe { ::std::slice::from_raw_parts_mut(buff.as_ptr() as *mut u32, 4) };
+/// Parse the header at the start of `buff`
+///
+/// `version` is the format used on the connection, `None` until the first message is read.
+/// Only that message decides it, if it is the `Hello` of a client its layout tells the formats
+/// apart, otherwise the current format is used. The format is kept for the rest of the
+/// connection, the same way libpipewire does it, later messages with the same object id and
+/// opcode (eg. core `Done` events) don't change it.
+pub fn read_header<'a>(
+    buff: &'a [u8],
+    version: &mut Option<ProtocolVersion>,
+) -> Option<(&'a [u8], Header)> {
+    let word = |i: usize| -> Option<u32> {
+        let bytes = buff.get(i * 4..(i + 1) * 4)?;
+        Some(u32::from_ne_bytes(bytes.try_into().unwrap()))
+    };
 
-        let header = Header::deserialize(buff);
+    let object_id = word(0)?;
+    let opcode = word(1)? >> 24;
 
-        // if core hello message
-        if header.object_id == 0 && header.opcode == 1 {
-            // Check the type of the pod in the message. Old versions
+    let version = match *version {
+        Some(version) => version,
+        None => {
+            // if core hello message, check the type of the pod in the message. Old versions
             // should not have 0 there, new versions keep the number of file
             // descriptors, which should be 0 for the first message.
             //
-            // Although libpipewire checks if the pod size is > 4 because "the unit test adds one fd
-            // in the first message.". So I guess we'll do that as well.
-            if header.n_fds >= 4 {
-                unimplemented!("Old version of the protocol");
-                // let hdr_size = 8;
+            // Although libpipewire checks if the pod size is > 4 because "the unit test adds one
+            // fd in the first message.". So I guess we'll do that as well.
+            let detected = if object_id == 0 && opcode == 1 && word(3)? >= 4 {
+                ProtocolVersion::V0
             } else {
-                // let hdr_size = 16;
-            }
+                ProtocolVersion::V3
+            };
+            *version = Some(detected);
+            detected
         }
+    };
 
-        header
+    let header = match version {
+        ProtocolVersion::V0 => Header::deserialize_v0(&[word(0)?, word(1)?]),
+        ProtocolVersion::V3 => Header::deserialize(&[word(0)?, word(1)?, word(2)?, word(3)?]),
     };
 
-    let buff = &buff[HDR_SIZE..];
+    let buff = &buff[version.header_size()..];
     Some((buff, header))
 }
 
-fn read_body_and_footer<'a>(
-    buff: &'a [u8],
+fn read_body_and_footer(body: &[u8]) -> (PodDeserializer<'_>, Option<PodDeserializer<'_>>) {
+    let (body, footer) = pod::PodDeserializer::new(body);
+    let footer = if footer.is_empty() {
+        None
+    } else {
+        let (footer, rest) = pod::PodDeserializer::new(footer);
+        debug_assert!(rest.is_empty());
+        Some(footer)
+    };
+
+    (body, footer)
+}
+
+/// Take the fds of a message out of the received ones
+///
+/// v0 headers don't count fds, the message gets every fd received so far instead, the same way
+/// libpipewire's compat path hands the fds of a read to the messages in it.
+fn read_fds(
+    fds: &mut VecDeque<OwnedFd>,
     header: &Header,
-) -> Option<(&'a [u8], PodDeserializer<'a>, Option<PodDeserializer<'a>>)> {
-    let len = header.len as usize;
-    let (body, footer) = {
-        let body = &buff[..header.len as usize];
-        debug_assert_eq!(body.len(), header.len as usize);
+    version: ProtocolVersion,
+) -> Result<Vec<Option<OwnedFd>>> {
+    if version == ProtocolVersion::V0 {
+        return Ok(fds.drain(..).map(Some).collect());
+    }
 
-        let (body, footer) = pod::PodDeserializer::new(body);
-        let footer = if footer.is_empty() {
-            None
-        } else {
-            let (footer, rest) = pod::PodDeserializer::new(footer);
-            debug_assert!(rest.is_empty());
-            Some(footer)
-        };
+    let n_fds = header.n_fds as usize;
+    if fds.len() < n_fds {
+        return Err(Error::MissingFds {
+            expected: header.n_fds,
+            received: fds.len(),
+        });
+    }
+    Ok(fds.drain(..n_fds).map(Some).collect())
+}
 
-        (body, footer)
+/// Map a v0 pod type id to the current one
+///
+/// The v0 type list lacks `Sequence` and `Choice`, so every type after `Object` got shifted.
+/// `Prop` has no counterpart, the v0 object layout got replaced by properties.
+pub fn pod_type_from_v0(ty: u32) -> Option<SpaType> {
+    let ty = match ty {
+        0..=15 => ty,
+        16 => SpaType::Pointer as u32,
+        17 => SpaType::Fd as u32,
+        19 => SpaType::Pod as u32,
+        _ => return None,
     };
+    SpaType::from_raw(ty)
+}
 
-    let buff = &buff[len..];
+/// Type of v0 object properties, they have no counterpart, see [`pods_from_v0`]
+const V0_TYPE_PROP: u32 = 18;
+
+/// Opcode of `UpdateTypes`, both of the core method and the core event
+const V0_UPDATE_TYPES: u8 = 0;
+
+/// Rewrite the type ids of v0 pods in place, the same way libpipewire's compat layer does
+///
+/// Pod type codes are remapped, ids the peer registered in `types` are replaced by their current
+/// ids. That covers `Id` pods, the type and id of objects and the keys of object properties.
+/// Structs, arrays and objects are walked recursively.
+///
+/// Object properties keep their v0 layout, a `Prop` pod with the alternative values in place of
+/// a `Choice`, only their key and values get translated.
+fn pods_from_v0(pods: &mut [u8], types: &TypeMap) {
+    let mut offset = 0;
+    while let Some(pod) = pods.get_mut(offset..offset + 8) {
+        let size = u32::from_ne_bytes(pod[0..4].try_into().unwrap()) as usize;
+        let raw_ty = u32::from_ne_bytes(pod[4..8].try_into().unwrap());
+
+        let ty = pod_type_from_v0(raw_ty);
+        if let Some(ty) = ty {
+            pod[4..8].copy_from_slice(&(ty as u32).to_ne_bytes());
+        }
 
-    Some((buff, body, footer))
+        let body_start = offset + 8;
+        let body_end = (body_start + size).min(pods.len());
+        let body = &mut pods[body_start..body_end];
+
+        match ty {
+            Some(SpaType::Id) => translate_ids(body, types),
+            Some(SpaType::Struct) => pods_from_v0(body, types),
+            Some(SpaType::Array) => array_from_v0(body, types),
+            Some(SpaType::Object) if body.len() >= 8 => {
+                // v0 objects start with the id, followed by the type
+                body[..8].rotate_left(4);
+                translate_ids(&mut body[..8], types);
+                pods_from_v0(&mut body[8..], types);
+            }
+            None if raw_ty == V0_TYPE_PROP && body.len() >= 8 => {
+                // Key and flags, followed by the values laid out like the body of an array
+                translate_ids(&mut body[..4], types);
+                array_from_v0(&mut body[8..], types);
+            }
+            _ => {}
+        }
+
+        offset = body_start + size.next_multiple_of(8);
+    }
 }
 
-fn read_fds(fds: &mut VecDeque<RawFd>, header: &Header) -> Vec<RawFd> {
-    let mut msg_fds = Vec::with_capacity(header.n_fds as usize);
-    for _ in 0..header.n_fds {
-        match fds.pop_front() {
-            Some(fd) => msg_fds.push(fd),
-            None => todo!("missing fds"),
+/// Remap the child type of a v0 array body, its elements get translated if they are ids
+fn array_from_v0(body: &mut [u8], types: &TypeMap) {
+    let Some(child) = body.get_mut(0..8) else {
+        return;
+    };
+    let child_size = u32::from_ne_bytes(child[0..4].try_into().unwrap());
+    let Some(child_ty) = pod_type_from_v0(u32::from_ne_bytes(child[4..8].try_into().unwrap()))
+    else {
+        return;
+    };
+    child[4..8].copy_from_slice(&(child_ty as u32).to_ne_bytes());
+
+    if child_ty == SpaType::Id && child_size == 4 {
+        translate_ids(&mut body[8..], types);
+    }
+}
+
+/// Replace every id registered in `types` by its current id, ids are 4 bytes each
+fn translate_ids(ids: &mut [u8], types: &TypeMap) {
+    for id in ids.chunks_exact_mut(4) {
+        let raw = u32::from_ne_bytes(id.try_into().unwrap());
+        if let Some(current) = types.translate(raw) {
+            id.copy_from_slice(&current.to_ne_bytes());
         }
     }
-    msg_fds
 }
 
-pub fn read_msg<'a>(buff: &'a [u8], fds: &mut VecDeque<RawFd>) -> Option<(&'a [u8], Message<'a>)> {
-    let (buff, header) = read_header(buff)?;
-    let (buff, body, footer) = read_body_and_footer(buff, &header)?;
-    let fds = read_fds(fds, &header);
+/// Record the types registered with a v0 `UpdateTypes`
+///
+/// The body is a struct of the first id, the number of types and their names.
+fn update_types(body: &[u8], types: &mut TypeMap) -> pod::deserialize::Result<()> {
+    let (body, _) = PodDeserializer::new(body);
+    let mut body = body.as_struct()?;
+
+    let first_id = body.pop_field()?.as_u32()?;
+    let n_types = body.pop_field()?.as_u32()?;
+    let names = (0..n_types)
+        .map(|_| Ok(String::from_utf8_lossy(body.pop_field()?.as_str()?).into_owned()))
+        .collect::<pod::deserialize::Result<Vec<_>>>()?;
+
+    types.update(first_id, names);
+    Ok(())
+}
+
+/// Read a single message from the start of `buff`
+///
+/// Returns `None` if `buff` does not hold a complete message yet. Messages of v0 peers get
+/// their pods converted in place, which is why the buffer has to be mutable, and take all of
+/// the fds received so far. Their `UpdateTypes` messages are recorded in `format`.
+pub fn read_msg<'a>(
+    buff: &'a mut [u8],
+    fds: &mut VecDeque<OwnedFd>,
+    format: &mut PeerFormat,
+) -> Result<Option<(&'a mut [u8], Message<'a>)>> {
+    let Some((body, header)) = read_header(buff, &mut format.version) else {
+        return Ok(None);
+    };
+    let len = header.len as usize;
+    if body.len() < len {
+        return Ok(None);
+    }
+
+    let version = format.version.unwrap_or_default();
+    let header_size = version.header_size();
+    let (msg, rest) = buff.split_at_mut(header_size + len);
+    let body = &mut msg[header_size..];
+
+    if version == ProtocolVersion::V0 {
+        if header.object_id == 0 && header.opcode == V0_UPDATE_TYPES {
+            update_types(body, &mut format.types).map_err(Error::TypeMap)?;
+        }
+        pods_from_v0(body, &format.types);
+    }
+
+    let (body, footer) = read_body_and_footer(body);
+    let fds = read_fds(fds, &header, version)?;
 
     let msg = Message {
         header,
@@ -213,19 +916,16 @@ pub fn read_msg<'a>(buff: &'a [u8], fds: &mut VecDeque<RawFd>) -> Option<(&'a [u
         fds,
     };
 
-    Some((buff, msg))
+    Ok(Some((rest, msg)))
 }
 
-fn send_msg(stream: &UnixStream, bytes: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+fn send_msg(stream: &UnixStream, bytes: &[u8], fds: &[BorrowedFd]) -> io::Result<usize> {
     let iov = [IoSlice::new(bytes)];
 
     if !fds.is_empty() {
         let mut space = vec![MaybeUninit::uninit(); rustix::cmsg_space!(ScmRights(fds.len()))];
         let mut cmsgs = SendAncillaryBuffer::new(&mut space);
 
-        // TODO: Just aceept BorrowedFd
-        let fds = unsafe { std::mem::transmute::<&[RawFd], &[BorrowedFd]>(fds) };
-
         cmsgs.push(SendAncillaryMessage::ScmRights(fds));
         Ok(rustix::net::sendmsg(
             stream,
@@ -243,65 +943,3 @@ fn send_msg(stream: &UnixStream, bytes: &[u8], fds: &[RawFd]) -> io::Result<usiz
         )?)
     }
 }
-
-fn fill_buf(stream: &UnixStream, buffer: &mut MessageBuffer, mut needed: usize) -> io::Result<()> {
-    while needed > 0 {
-        if buffer.start == buffer.end {
-            buffer.start = 0;
-            buffer.end = 0;
-
-            let mut space = [MaybeUninit::uninit(); rustix::cmsg_space!(ScmRights(MAX_FDS_OUT))];
-            let mut cmsgs = RecvAncillaryBuffer::new(&mut space);
-
-            let mut iov = [IoSliceMut::new(&mut buffer.buffer)];
-
-            let msg = rustix::net::recvmsg(stream, &mut iov, &mut cmsgs, RecvFlags::CMSG_CLOEXEC)?;
-
-            let received_fds = cmsgs
-                .drain()
-                .filter_map(|cmsg| match cmsg {
-                    RecvAncillaryMessage::ScmRights(fds) => Some(fds),
-                    _ => None,
-                })
-                .flatten()
-                .map(|fd| fd.into_raw_fd());
-            buffer.fds.extend(received_fds);
-
-            buffer.end = msg.bytes;
-
-            if buffer.start == buffer.end {
-                todo!("end of buffer");
-            }
-        }
-
-        let read = needed.min(buffer.len());
-        let start = buffer.start;
-        buffer
-            .staging_buffer
-            .extend_from_slice(&buffer.buffer[start..start + read]);
-        needed -= read;
-        buffer.start += read;
-    }
-
-    Ok(())
-}
-
-fn rcv_msg<'a>(stream: &UnixStream, buffer: &'a mut MessageBuffer) -> io::Result<Message<'a>> {
-    buffer.staging_buffer.clear();
-    fill_buf(stream, buffer, 16)?;
-    let (_, header) = read_header(&buffer.staging_buffer).unwrap();
-
-    buffer.staging_buffer.clear();
-    fill_buf(stream, buffer, header.len as usize)?;
-
-    let (_, body, footer) = read_body_and_footer(&buffer.staging_buffer, &header).unwrap();
-
-    let fds = read_fds(&mut buffer.fds, &header);
-
-    Ok(Message {
-        header,
-        body,
-        footer,
-        fds,
-    })
-}
@@ -1,54 +1,309 @@
 use std::{
-    any::Any,
+    any::{type_name, Any},
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
     io,
-    os::fd::{AsRawFd, RawFd},
+    marker::PhantomData,
+    os::{
+        fd::{AsRawFd, BorrowedFd, OwnedFd, RawFd},
+        unix::net::UnixStream,
+    },
     path::Path,
+    rc::{Rc, Weak},
+    time::Duration,
 };
 
+use libspa_consts::{PwLinkState, SpaEnum};
+
 use crate::{
-    connection::{Connection, Message, MessageBuffer},
+    connection::{ConnectError, Connection, FooterCore, Message, MessageBuffer, Messages},
+    link::{LinkSettled, LinkShared, LinkSpec, LinkStatus},
+    memory_registry::MemoryRegistry,
     object_map::{Object, ObjectMap, ObjectType},
-    protocol::{
-        pw_client, pw_client_node, pw_core, pw_device, pw_link, pw_node, pw_port, pw_registry,
-    },
-    proxy::{
-        ObjectId, Proxy, PwClient, PwClientNode, PwCore, PwDevice, PwLink, PwNode, PwPort,
-        PwRegistry,
-    },
+    protocol::{self, pw_client, pw_core, pw_link, AnyEvent, FromAnyEventRef, MethodSerialize},
+    proxy::{ObjectId, Proxy, PwClient, PwCore, PwLink, Seq},
+    Error, Result,
 };
 
-struct CallbackArgs<'a, D> {
-    ctx: &'a mut Context<D>,
-    state: &'a mut D,
-    object_id: ObjectId,
-    object_data: &'a mut Box<dyn Any>,
-    event: Box<dyn Any>,
+/// Statically typed event handler, implemented by the state for every proxy type and
+/// user data type it binds objects with
+///
+/// The user data type is fixed when the handler gets attached, see [`PwRegistry::bind`]
+/// and [`Context::set_object_dispatch`], events are dispatched without boxing or downcasting.
+///
+/// [`PwRegistry::bind`]: crate::proxy::PwRegistry::bind
+pub trait Dispatch<P: Proxy, U>: Sized {
+    fn event(state: &mut Self, ctx: &mut Context<Self>, data: &mut U, proxy: P, event: P::Event);
+}
+
+/// Events handler of a single object
+trait ObjectHandler<D> {
+    /// Gives the event back if it is not meant for this handler
+    fn event(
+        &mut self,
+        state: &mut D,
+        ctx: &mut Context<D>,
+        object_id: ObjectId,
+        event: AnyEvent,
+    ) -> Option<AnyEvent>;
+    fn data(&self) -> &dyn Any;
+    fn data_mut(&mut self) -> &mut dyn Any;
+}
+
+struct CallbackHandler<P, T, F> {
+    cb: F,
+    data: T,
+    _proxy: PhantomData<fn(P)>,
+}
+
+impl<D, P, T, F> ObjectHandler<D> for CallbackHandler<P, T, F>
+where
+    P: Proxy,
+    T: 'static,
+    F: FnMut(&mut D, &mut Context<D>, &mut T, P, P::Event),
+{
+    fn event(
+        &mut self,
+        state: &mut D,
+        ctx: &mut Context<D>,
+        object_id: ObjectId,
+        event: AnyEvent,
+    ) -> Option<AnyEvent> {
+        // The handler got attached with a proxy of a different type than the object
+        let event = match P::Event::try_from(event) {
+            Ok(event) => event,
+            Err(event) => return Some(event),
+        };
+        (self.cb)(state, ctx, &mut self.data, P::from_id(object_id), event);
+        None
+    }
+
+    fn data(&self) -> &dyn Any {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut dyn Any {
+        &mut self.data
+    }
+}
+
+struct DispatchHandler<P, U> {
+    data: U,
+    _proxy: PhantomData<fn(P)>,
+}
+
+impl<D, P, U> ObjectHandler<D> for DispatchHandler<P, U>
+where
+    D: Dispatch<P, U>,
+    P: Proxy,
+    U: 'static,
+{
+    fn event(
+        &mut self,
+        state: &mut D,
+        ctx: &mut Context<D>,
+        object_id: ObjectId,
+        event: AnyEvent,
+    ) -> Option<AnyEvent> {
+        let event = match P::Event::try_from(event) {
+            Ok(event) => event,
+            Err(event) => return Some(event),
+        };
+        D::event(state, ctx, &mut self.data, P::from_id(object_id), event);
+        None
+    }
+
+    fn data(&self) -> &dyn Any {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut dyn Any {
+        &mut self.data
+    }
+}
+
+type ListenerCallback<D> = Box<dyn FnMut(&mut D, &mut Context<D>, ObjectId, &AnyEvent)>;
+
+struct Listener<D> {
+    /// Cleared when the [`ListenerHandle`] gets dropped
+    alive: Rc<Cell<bool>>,
+    cb: ListenerCallback<D>,
+}
+
+/// Keeps a listener registered, it gets removed once the handle is dropped
+///
+/// The listener stops seeing events right away, its callback is freed on the next dispatch.
+/// See [`Context::add_listener`].
+#[must_use = "the listener is removed as soon as the handle is dropped"]
+#[derive(Debug)]
+pub struct ListenerHandle {
+    alive: Rc<Cell<bool>>,
+    object_id: u32,
+    /// Objects with dropped listeners, swept by the context on the next dispatch
+    dropped: Weak<RefCell<Vec<u32>>>,
+}
+
+impl Drop for ListenerHandle {
+    fn drop(&mut self) {
+        self.alive.set(false);
+        if let Some(dropped) = self.dropped.upgrade() {
+            dropped.borrow_mut().push(self.object_id);
+        }
+    }
 }
 
+/// Handler, listeners and not yet handled events of an object
 struct ObjectState<D> {
-    cb: Box<dyn FnMut(CallbackArgs<D>)>,
-    data: Box<dyn Any>,
+    handler: Option<Box<dyn ObjectHandler<D>>>,
+    listeners: Vec<Listener<D>>,
+    /// Destroyed locally, waiting for `RemoveId` before the id can be reused
+    zombie: bool,
+    /// Events that arrived before a handler got attached
+    pending: VecDeque<AnyEvent>,
+}
+
+impl<D> ObjectState<D> {
+    fn new() -> Self {
+        Self {
+            handler: None,
+            listeners: Vec::new(),
+            zombie: false,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+type UnhandledCallback<D> = Box<dyn FnMut(&mut D, &mut Context<D>, ObjectId, AnyEvent)>;
+
+type ErrorCallback<D> = Box<dyn FnMut(&mut D, &mut Context<D>, ObjectId, ProxyError)>;
+
+type MessageErrorCallback<D> = Box<dyn FnMut(&mut D, &mut Context<D>, Error)>;
+
+type LinkCallback<D> = Box<dyn FnOnce(&mut D, &mut Context<D>, PwLink, LinkStatus)>;
+
+/// State of a link created with [`Context::create_link`]
+struct LinkTracker<D> {
+    shared: Rc<RefCell<LinkShared>>,
+    linger: bool,
+    /// Called once the link settles
+    callback: Option<LinkCallback<D>>,
+}
+
+/// Events queued for an object without a handler, older ones are dropped past that
+const PENDING_EVENTS_MAX: usize = 128;
+
+/// Seqs wrap around at the same point as in libpipewire (`SPA_ASYNC_SEQ_MASK`)
+const SEQ_MASK: u32 = (1 << 30) - 1;
+
+/// Error reported by the daemon for a method sent by a proxy, see [`Context::set_error_callback`]
+#[derive(Debug, Clone)]
+pub struct ProxyError {
+    /// Seq of the failing method, as returned when the method was sent
+    pub seq: Seq,
+    /// A negative errno style error code
+    pub res: i32,
+    pub message: String,
+}
+
+bitflags::bitflags! {
+    /// Core events that [`Context`] takes care of on its own, see [`Context::set_housekeeping`]
+    ///
+    /// The events are forwarded to the core callback either way.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Housekeeping: u32 {
+        /// Answer `Ping` with a `Pong`, the daemon kills clients that don't
+        const PONG = 1 << 0;
+        /// Free the local object on `RemoveId`
+        const REMOVE_ID = 1 << 1;
+        /// Keep track of `AddMem`/`RemoveMem` in [`Context::memory_registry`],
+        /// the fd gets taken out of the `AddMem` event
+        const MEMORY = 1 << 2;
+        /// Record global ids announced by `BoundId`, see [`Context::global_id`]
+        const BOUND_ID = 1 << 3;
+    }
+}
+
+impl Default for Housekeeping {
+    fn default() -> Self {
+        Self::all()
+    }
 }
 
 pub struct Context<D = ()> {
     conn: Connection,
-    map: ObjectMap<Option<ObjectState<D>>>,
+    map: ObjectMap<ObjectState<D>>,
+    /// Objects that got a handler while they had pending events
+    replay: Vec<u32>,
+    /// Objects whose listener handles got dropped, see [`Context::remove_dead_listeners`]
+    dropped_listeners: Rc<RefCell<Vec<u32>>>,
+    unhandled_callback: Option<UnhandledCallback<D>>,
+    message_error_callback: Option<MessageErrorCallback<D>>,
+    housekeeping: Housekeeping,
+    memory: MemoryRegistry,
+    error_callbacks: HashMap<u32, ErrorCallback<D>>,
+    links: HashMap<u32, LinkTracker<D>>,
+    generation: u64,
+    next_seq: u32,
+    /// Taken out while dispatching
+    buffer: Option<MessageBuffer>,
+    /// Seq of the sync [`Context::roundtrip`] waits for, cleared once its `Done` arrived
+    roundtrip_seq: Option<i32>,
 }
 
 impl<D> Context<D> {
     pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::from_connection(Connection::connect(path)?))
+    }
+
+    /// Connect to the daemon socket resolved from the environment, the same way libpipewire does
+    pub fn connect_default() -> Result<Self, ConnectError> {
+        Ok(Self::from_connection(Connection::connect_default()?))
+    }
+
+    /// Same as [`Context::connect_default`] but prefers the `pipewire-0-manager` socket
+    pub fn connect_default_manager() -> Result<Self, ConnectError> {
+        Ok(Self::from_connection(Connection::connect_default_manager()?))
+    }
+
+    /// Use an already connected socket, eg. one handed over by a portal or a parent process
+    pub fn from_fd(fd: OwnedFd) -> Self {
+        Self::from_connection(Connection::from_fd(fd))
+    }
+
+    /// Use an already connected socket
+    pub fn from_stream(stream: UnixStream) -> Self {
+        Self::from_connection(Connection::from_stream(stream))
+    }
+
+    fn from_connection(conn: Connection) -> Self {
         let mut this = Self {
-            conn: Connection::connect(path)?,
+            conn,
             map: ObjectMap::new(),
+            replay: Vec::new(),
+            dropped_listeners: Rc::default(),
+            unhandled_callback: None,
+            message_error_callback: None,
+            housekeeping: Housekeeping::default(),
+            memory: MemoryRegistry::new(),
+            error_callbacks: HashMap::new(),
+            links: HashMap::new(),
+            generation: 0,
+            next_seq: 0,
+            buffer: Some(MessageBuffer::new()),
+            roundtrip_seq: None,
         };
 
-        let core_id = this.new_object(ObjectType::Core).protocol_id();
+        let core_id = this
+            .new_object(ObjectType::Core, PwCore::VERSION)
+            .protocol_id();
         assert_eq!(core_id, 0);
 
-        let client_id = this.new_object(ObjectType::Client).protocol_id();
+        let client_id = this
+            .new_object(ObjectType::Client, PwClient::VERSION)
+            .protocol_id();
         assert_eq!(client_id, 1);
 
-        Ok(this)
+        this
     }
 
     pub fn core(&self) -> PwCore {
@@ -59,185 +314,782 @@ impl<D> Context<D> {
         PwClient::new(pw_client::OBJECT_ID)
     }
 
-    pub fn new_object(&mut self, kind: ObjectType) -> ObjectId {
+    /// Allocate an id for an object of type `kind`, bound with interface `version`
+    pub fn new_object(&mut self, kind: ObjectType, version: u32) -> ObjectId {
         let new_id = self.map.insert_new(Object {
             interface: kind,
-            version: 3,
-            data: None,
+            version,
+            global_id: None,
+            data: ObjectState::new(),
         });
 
         ObjectId::new(new_id)
     }
 
-    pub fn send_msg(&mut self, bytes: &[u8], fds: &[RawFd]) -> io::Result<usize> {
-        self.conn.send_msg(bytes, fds)
+    /// Serialize and queue a method of the object `object_id`
+    ///
+    /// Methods introduced in a later version than the one the object was bound with are
+    /// rejected with [`Error::Version`].
+    ///
+    /// ```
+    /// use std::os::unix::net::UnixStream;
+    ///
+    /// use ripewire::{
+    ///     context::Context,
+    ///     object_map::ObjectType,
+    ///     protocol::pw_node::NodeCommand,
+    ///     proxy::{Proxy, PwNode},
+    ///     Error,
+    /// };
+    ///
+    /// let (socket, _daemon) = UnixStream::pair().unwrap();
+    /// let mut ctx = Context::<()>::from_stream(socket);
+    ///
+    /// // `SendCommand` was introduced in version 3 of the node interface
+    /// let node = PwNode::from_id(ctx.new_object(ObjectType::Node, 0));
+    /// let err = node.send_command(&mut ctx, NodeCommand::Suspend).unwrap_err();
+    /// assert!(matches!(err, Error::Version { since: 3, version: 0, .. }));
+    /// ```
+    pub fn send_method<M: MethodSerialize>(
+        &mut self,
+        object_id: &ObjectId,
+        method: &M,
+    ) -> Result<Seq> {
+        let obj = self
+            .map
+            .find(object_id.protocol_id())
+            .ok_or(Error::UnknownObject(object_id.protocol_id()))?;
+        if M::SINCE > obj.version {
+            return Err(Error::Version {
+                interface: obj.interface.clone(),
+                opcode: M::OPCODE,
+                since: M::SINCE,
+                version: obj.version,
+            });
+        }
+
+        let (msg, fds) = protocol::create_msg_with_fds(object_id.protocol_id(), method);
+        self.send_msg(&msg, &fds)
     }
 
-    pub fn rcv_msg<'a>(&mut self, buff: &'a mut MessageBuffer) -> io::Result<Message<'a>> {
+    /// Queue a serialized message, see [`Context::flush`]
+    ///
+    /// The message gets the next sequence number written into its header.
+    pub fn send_msg(&mut self, bytes: &[u8], fds: &[BorrowedFd]) -> Result<Seq> {
+        let seq = self.next_seq;
+        self.conn.send_msg_with_seq(bytes, fds, seq)?;
+        self.next_seq = (seq + 1) & SEQ_MASK;
+        Ok(Seq::new(seq))
+    }
+
+    /// Seq the next queued message gets
+    pub(crate) fn next_seq(&self) -> Seq {
+        Seq::new(self.next_seq)
+    }
+
+    /// Write all queued messages to the socket
+    ///
+    /// If the socket is full an error for which [`Error::is_would_block`] is true is returned,
+    /// wait for the socket to become writable (`POLLOUT`) and call this again.
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.conn.flush()?)
+    }
+
+    /// Are there any messages waiting to be flushed
+    pub fn has_pending_writes(&self) -> bool {
+        self.conn.has_pending_writes()
+    }
+
+    /// Read whatever the socket has to offer into the internal buffer
+    ///
+    /// Together with [`Context::dispatch_pending`] this allows driving the context from
+    /// an external event loop, once the fd becomes readable.
+    pub fn read(&mut self) -> Result<usize> {
+        let buffer = self.buffer.as_mut().ok_or(Error::NestedDispatch)?;
+        self.conn.read(buffer)
+    }
+
+    /// Dispatch messages that were already read, without touching the socket
+    ///
+    /// Returns the number of dispatched messages.
+    pub fn dispatch_pending(&mut self, state: &mut D) -> Result<usize> {
+        let mut buffer = self.buffer.take().ok_or(Error::NestedDispatch)?;
+        let res = self.dispatch_buffer(state, &mut buffer);
+        self.buffer = Some(buffer);
+        res
+    }
+
+    /// Messages that fail to dispatch are passed to the message error callback, only errors of
+    /// the connection itself stop the dispatch, see [`Context::set_message_error_callback`]
+    fn dispatch_buffer(&mut self, state: &mut D, buffer: &mut MessageBuffer) -> Result<usize> {
+        let mut count = self.replay_pending(state);
+        for msg in buffer.messages() {
+            match msg.and_then(|msg| self.dispatch_message(state, msg)) {
+                Ok(()) => count += 1,
+                Err(err) if err.is_fatal() => return Err(err),
+                Err(err) => self.dispatch_message_error(state, err),
+            }
+            count += self.replay_pending(state);
+        }
+        Ok(count)
+    }
+
+    /// Flush, wait for messages for up to `timeout` (forever if `None`) and dispatch them
+    ///
+    /// Returns the number of dispatched messages, 0 if the timeout expired.
+    pub fn blocking_dispatch(&mut self, state: &mut D, timeout: Option<Duration>) -> Result<usize> {
+        self.blocking_flush()?;
+
+        let buffer = self.buffer.as_ref().ok_or(Error::NestedDispatch)?;
+        if !buffer.has_message() && self.replay.is_empty() {
+            let timeout = timeout.map_or(-1, |timeout| {
+                i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX)
+            });
+            if !crate::poll(self.as_raw_fd(), timeout)? {
+                return Ok(0);
+            }
+
+            match self.read() {
+                Ok(_) => {}
+                // Spurious wakeup of a non-blocking socket
+                Err(err) if err.is_would_block() => return Ok(0),
+                Err(err) => return Err(err),
+            }
+        }
+
+        self.dispatch_pending(state)
+    }
+
+    /// Block until the daemon processed everything that was sent so far
+    ///
+    /// Sends a [`pw_core::methods::Sync`] with a fresh seq and dispatches events
+    /// until the matching `Done` comes back.
+    pub fn roundtrip(&mut self, state: &mut D) -> Result<()> {
+        // The sync carries the seq of its own message
+        let seq = self.next_seq;
+        self.core().sync(self, pw_core::OBJECT_ID, seq)?;
+
+        // Syncs sent by callbacks meanwhile might be done in the same read, after ours
+        self.roundtrip_seq = Some(seq as i32);
+        while self.roundtrip_seq.is_some() {
+            if let Err(err) = self.blocking_dispatch(state, None) {
+                self.roundtrip_seq = None;
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn blocking_flush(&mut self) -> Result<()> {
+        loop {
+            match self.flush() {
+                Err(err) if err.is_would_block() => {
+                    crate::poll_events(self.as_raw_fd(), libc::POLLOUT, -1)?;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    /// Receive messages from the socket, see [`Connection::rcv_msg`]
+    pub fn rcv_msg<'a>(&mut self, buff: &'a mut MessageBuffer) -> Result<Messages<'a>> {
         self.conn.rcv_msg(buff)
     }
 
+    /// Select which core events are handled by the context itself, all of them by default
+    pub fn set_housekeeping(&mut self, housekeeping: Housekeeping) {
+        self.housekeeping = housekeeping;
+    }
+
+    pub fn housekeeping(&self) -> Housekeeping {
+        self.housekeeping
+    }
+
+    /// Memory shared by the daemon, see [`Housekeeping::MEMORY`]
+    pub fn memory_registry(&self) -> &MemoryRegistry {
+        &self.memory
+    }
+
+    /// Id of the global `object_id` is bound to, see [`Housekeeping::BOUND_ID`]
+    pub fn global_id(&self, object_id: &ObjectId) -> Option<u32> {
+        self.map.find(object_id.protocol_id())?.global_id
+    }
+
+    /// Last registry generation announced by the daemon in a message footer
+    ///
+    /// Gets sent back with [`PwRegistry::bind`], so that the daemon can tell whether
+    /// the global was removed in the meantime.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Destroy the object of `proxy`, see [`Proxy::destroy`]
+    ///
+    /// Fails with [`Error::UnknownObject`] if the object was destroyed already and with
+    /// [`Error::Singleton`] for the core and the client.
+    pub fn destroy<P: Proxy + ?Sized>(&mut self, proxy: &P) -> Result<Seq> {
+        let id = proxy.id();
+        if [pw_core::OBJECT_ID, pw_client::OBJECT_ID].contains(&id.protocol_id()) {
+            return Err(Error::Singleton(id.protocol_id()));
+        }
+        // Destroying a zombie again would destroy whatever object reuses its id
+        if self.map.find(id.protocol_id()).is_none() || self.is_zombie(&id) {
+            return Err(Error::UnknownObject(id.protocol_id()));
+        }
+
+        let seq = self.core().destroy_object(self, id.clone())?;
+
+        if let Some(obj) = self.map.find_mut(id.protocol_id()) {
+            obj.data.zombie = true;
+        }
+
+        Ok(seq)
+    }
+
+    /// Was the object destroyed, with its id not yet released by the daemon
+    pub fn is_zombie(&self, object_id: &ObjectId) -> bool {
+        self.map
+            .find(object_id.protocol_id())
+            .is_some_and(|obj| obj.data.zombie)
+    }
+
     pub fn object_type(&mut self, object_id: &ObjectId) -> Option<ObjectType> {
         let obj = self.map.find(object_id.protocol_id())?;
         Some(obj.interface.clone())
     }
 
-    pub fn dispatch_event(&mut self, state: &mut D, msg: Message) {
+    /// Interface version the object was bound with
+    pub fn object_version(&self, object_id: &ObjectId) -> Option<u32> {
+        Some(self.map.find(object_id.protocol_id())?.version)
+    }
+
+    /// Dispatch a message received with [`Context::rcv_msg`]
+    ///
+    /// Events queued for objects that got a handler in the meantime are handed to it first,
+    /// the ones for handlers attached while dispatching `msg` right after.
+    pub fn dispatch_event(&mut self, state: &mut D, msg: Message) -> Result<()> {
+        self.replay_pending(state);
+        self.dispatch_message(state, msg)?;
+        self.replay_pending(state);
+        Ok(())
+    }
+
+    fn dispatch_message(&mut self, state: &mut D, mut msg: Message) -> Result<()> {
+        self.remove_dead_listeners();
+
+        for footer in msg.core_footers().map_err(Error::Footer)? {
+            match footer {
+                FooterCore::Generation(generation) => self.generation = generation,
+            }
+        }
+
         let id = ObjectId::new(msg.header.object_id);
+        let (ty, version) = self
+            .map
+            .find(msg.header.object_id)
+            .map(|obj| (obj.interface.clone(), obj.version))
+            .ok_or(Error::UnknownObject(msg.header.object_id))?;
+
+        let event =
+            AnyEvent::deserialize(&ty, version, msg.header.opcode, &mut msg.body, &mut msg.fds)?;
 
-        match self.object_type(&id).unwrap() {
-            ObjectType::Core => {
-                let mut pod = msg.body;
-                let event =
-                    pw_core::Event::deserialize(msg.header.opcode, &mut pod, &msg.fds).unwrap();
+        if self.is_zombie(&id) {
+            self.dispatch_unhandled(state, id, event);
+            return Ok(());
+        }
 
-                let remove_id = if let pw_core::Event::RemoveId(ref event) = event {
-                    Some(event.id)
-                } else {
-                    None
+        let AnyEvent::Core(mut event) = event else {
+            if let AnyEvent::Link(pw_link::Event::Info(info)) = &event {
+                let status = LinkStatus {
+                    state: info.state,
+                    error: info.error.clone(),
                 };
+                self.update_link(state, id.protocol_id(), status);
+            }
+            self.dispatch_to_handler(state, id, event);
+            return Ok(());
+        };
 
-                let core = PwCore::from_id(id);
-                self.dispatch_event_inner(state, core, event);
+        self.core_housekeeping(state, &mut event)?;
 
-                if let Some(remove_id) = remove_id {
-                    self.map.remove(remove_id);
-                }
+        // Freed only after the user had a chance to look at them
+        let remove_id = match event {
+            pw_core::Event::RemoveId(ref event)
+                if self.housekeeping.contains(Housekeeping::REMOVE_ID) =>
+            {
+                Some(event.id)
             }
-            ObjectType::Client => {
-                let mut pod = msg.body;
-                let event =
-                    pw_client::Event::deserialize(msg.header.opcode, &mut pod, &msg.fds).unwrap();
+            _ => None,
+        };
+        let remove_mem = match event {
+            pw_core::Event::RemoveMem(ref event)
+                if self.housekeeping.contains(Housekeeping::MEMORY) =>
+            {
+                Some(event.clone())
+            }
+            _ => None,
+        };
+
+        self.dispatch_to_handler(state, id, AnyEvent::Core(event));
+
+        if let Some(remove_id) = remove_id {
+            self.remove_object(state, remove_id);
+        }
+        if let Some(remove_mem) = remove_mem {
+            self.memory.remove_mem(&remove_mem);
+        }
 
-                let client = PwClient::from_id(id);
-                self.dispatch_event_inner(state, client, event);
+        Ok(())
+    }
+
+    /// Handle core events selected by [`Housekeeping`], before they get forwarded
+    fn core_housekeeping(&mut self, state: &mut D, event: &mut pw_core::Event) -> Result<()> {
+        let housekeeping = self.housekeeping;
+
+        match event {
+            pw_core::Event::Error(event) => self.dispatch_error(state, event),
+            pw_core::Event::Done(done)
+                if done.id == Some(pw_core::OBJECT_ID) && self.roundtrip_seq == Some(done.seq) =>
+            {
+                self.roundtrip_seq = None;
+            }
+            pw_core::Event::Ping(ping) if housekeeping.contains(Housekeeping::PONG) => {
+                self.core().pong(self, ping.id, ping.seq)?;
             }
-            ObjectType::ClientNode => {
-                let mut pod = msg.body;
-                let event =
-                    pw_client_node::Event::deserialize(msg.header.opcode, &mut pod, &msg.fds)
-                        .unwrap();
-                let client_node = PwClientNode::from_id(id);
-                self.dispatch_event_inner(state, client_node, event);
+            pw_core::Event::AddMem(add_mem) if housekeeping.contains(Housekeeping::MEMORY) => {
+                self.memory.add_mem(add_mem);
             }
-            ObjectType::Registry => {
-                let mut pod = msg.body;
-                let event =
-                    pw_registry::Event::deserialize(msg.header.opcode, &mut pod, &msg.fds).unwrap();
+            pw_core::Event::BoundId(bound) if housekeeping.contains(Housekeeping::BOUND_ID) => {
+                if let Some(obj) = self.map.find_mut(bound.id) {
+                    obj.global_id = Some(bound.global_id);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Route a core error to the error callback of the proxy it is about
+    fn dispatch_error(&mut self, state: &mut D, event: &pw_core::events::Error) {
+        // Failing to create a link is reported this way
+        if self.links.contains_key(&event.id) {
+            let status = LinkStatus {
+                state: SpaEnum::Value(PwLinkState::Error),
+                error: Some(event.message.clone()),
+            };
+            self.update_link(state, event.id, status);
+        }
+
+        let Some(mut cb) = self.error_callbacks.remove(&event.id) else {
+            return;
+        };
+
+        let error = ProxyError {
+            seq: Seq::new(event.seq as u32),
+            res: event.res,
+            message: event.message.clone(),
+        };
+        cb(state, self, ObjectId::new(event.id), error);
 
-                let registry = PwRegistry::from_id(id);
-                self.dispatch_event_inner(state, registry, event);
+        // Keep the callback unless it got replaced in the meantime
+        self.error_callbacks.entry(event.id).or_insert(cb);
+    }
+
+    /// Free an object, its handler gets a `Removed` event and the events that are still
+    /// pending are passed to the unhandled callback
+    fn remove_object(&mut self, state: &mut D, id: u32) {
+        let object_id = ObjectId::new(id);
+        if let Some(removed) = self
+            .object_type(&object_id)
+            .and_then(|ty| AnyEvent::removed(&ty))
+        {
+            self.dispatch_to_handler(state, object_id, removed);
+        }
+
+        let pending = match self.map.find_mut(id) {
+            Some(obj) => std::mem::take(&mut obj.data.pending),
+            None => VecDeque::new(),
+        };
+        self.map.remove(id);
+        self.error_callbacks.remove(&id);
+
+        // Waiting for the link to settle is pointless now
+        if let Some(mut link) = self
+            .links
+            .remove(&id)
+            .filter(|link| !link.shared.borrow().settled)
+        {
+            let status = LinkStatus {
+                state: SpaEnum::Value(PwLinkState::Error),
+                error: Some("link was removed".into()),
+            };
+            link.shared.borrow_mut().update(status.clone());
+            if let Some(cb) = link.callback.take() {
+                cb(state, self, PwLink::from_id(ObjectId::new(id)), status);
             }
-            ObjectType::Device => {
-                let mut pod = msg.body;
-                let event =
-                    pw_device::Event::deserialize(msg.header.opcode, &mut pod, &msg.fds).unwrap();
+        }
 
-                let device = PwDevice::from_id(id);
-                self.dispatch_event_inner(state, device, event);
+        for event in pending {
+            self.dispatch_unhandled(state, ObjectId::new(id), event);
+        }
+    }
+
+    /// Record the new status of a tracked link, settling it calls its callback
+    fn update_link(&mut self, state: &mut D, id: u32, status: LinkStatus) {
+        let Some(link) = self.links.get_mut(&id) else {
+            return;
+        };
+        if !link.shared.borrow_mut().update(status.clone()) {
+            return;
+        }
+
+        let callback = link.callback.take();
+        let link = PwLink::from_id(ObjectId::new(id));
+        let failed = status.state == SpaEnum::Value(PwLinkState::Error);
+        if failed && !self.links[&id].linger && !self.is_zombie(&link.id()) {
+            // A failed send resurfaces with the next flush
+            let _ = self.destroy(&link);
+        }
+
+        if let Some(cb) = callback {
+            cb(state, self, link, status);
+        }
+    }
+
+    fn dispatch_to_handler(&mut self, state: &mut D, object_id: ObjectId, event: AnyEvent) {
+        let id = object_id.protocol_id();
+        let Some(obj) = self.map.find_mut(id) else {
+            self.dispatch_unhandled(state, object_id, event);
+            return;
+        };
+
+        if !obj.data.listeners.is_empty() {
+            let mut listeners = std::mem::take(&mut obj.data.listeners);
+            for listener in listeners.iter_mut() {
+                // The handle might have been dropped by a previous listener
+                if listener.alive.get() {
+                    (listener.cb)(state, self, object_id.clone(), &event);
+                }
             }
-            ObjectType::Node => {
-                let mut pod = msg.body;
-                let event =
-                    pw_node::Event::deserialize(msg.header.opcode, &mut pod, &msg.fds).unwrap();
 
-                let device = PwNode::from_id(id);
-                self.dispatch_event_inner(state, device, event);
+            let Some(obj) = self.map.find_mut(id) else {
+                return;
+            };
+            listeners.retain(|listener| listener.alive.get());
+            // Listeners added in the meantime go last
+            listeners.append(&mut obj.data.listeners);
+            obj.data.listeners = listeners;
+        }
+
+        let Some(obj) = self.map.find_mut(id) else {
+            return;
+        };
+
+        // Goes through the queue, so that events that are still pending are handled first
+        obj.data.pending.push_back(event);
+        self.dispatch_object_events(state, object_id);
+    }
+
+    /// Pass the pending events of an object to its handler, if it has one
+    ///
+    /// Returns the number of handled events.
+    fn dispatch_object_events(&mut self, state: &mut D, object_id: ObjectId) -> usize {
+        let id = object_id.protocol_id();
+        let mut count = 0;
+
+        loop {
+            let Some(obj) = self.map.find_mut(id) else {
+                return count;
+            };
+
+            let Some(mut handler) = obj.data.handler.take() else {
+                if obj.data.pending.len() <= PENDING_EVENTS_MAX {
+                    return count;
+                }
+                // Nobody showed up for a while, make room
+                if let Some(event) = obj.data.pending.pop_front() {
+                    self.dispatch_unhandled(state, object_id.clone(), event);
+                }
+                continue;
+            };
+
+            let Some(event) = obj.data.pending.pop_front() else {
+                obj.data.handler = Some(handler);
+                return count;
+            };
+
+            let unhandled = handler.event(state, self, object_id.clone(), event);
+            count += 1;
+
+            // Keep the handler unless it got replaced in the meantime
+            if let Some(obj) = self.map.find_mut(id) {
+                if obj.data.handler.is_none() {
+                    obj.data.handler = Some(handler);
+                }
             }
-            ObjectType::Link => {
-                let mut pod = msg.body;
-                let event =
-                    pw_link::Event::deserialize(msg.header.opcode, &mut pod, &msg.fds).unwrap();
 
-                let device = PwLink::from_id(id);
-                self.dispatch_event_inner(state, device, event);
+            if let Some(event) = unhandled {
+                self.dispatch_unhandled(state, object_id.clone(), event);
             }
-            ObjectType::Port => {
-                let mut pod = msg.body;
-                let event =
-                    pw_port::Event::deserialize(msg.header.opcode, &mut pod, &msg.fds).unwrap();
+        }
+    }
 
-                let device = PwPort::from_id(id);
-                self.dispatch_event_inner(state, device, event);
+    /// Free listeners whose handle got dropped, along with everything their callback holds
+    fn remove_dead_listeners(&mut self) {
+        let dropped = std::mem::take(&mut *self.dropped_listeners.borrow_mut());
+        for id in dropped {
+            if let Some(obj) = self.map.find_mut(id) {
+                obj.data.listeners.retain(|listener| listener.alive.get());
             }
-            ty => unimplemented!("{ty:?}"),
         }
     }
 
-    fn dispatch_event_inner<P>(&mut self, state: &mut D, object: P, event: P::Event)
-    where
-        P: Proxy,
-        P::Event: 'static,
-    {
-        let mut cb = {
-            let Some(obj) = self.map.find_mut(object.id().protocol_id()) else {
-                return;
-            };
-            obj.data.take()
+    /// Hand queued events to handlers that were attached since the last dispatch
+    fn replay_pending(&mut self, state: &mut D) -> usize {
+        let mut count = 0;
+        for id in std::mem::take(&mut self.replay) {
+            count += self.dispatch_object_events(state, ObjectId::new(id));
+        }
+        count
+    }
+
+    fn dispatch_unhandled(&mut self, state: &mut D, object_id: ObjectId, event: AnyEvent) {
+        let Some(mut cb) = self.unhandled_callback.take() else {
+            return;
         };
 
-        if let Some(cb) = cb.as_mut() {
-            (cb.cb)(CallbackArgs {
-                ctx: self,
-                state,
-                object_id: object.id(),
-                object_data: &mut cb.data,
-                event: Box::new(event),
-            });
+        cb(state, self, object_id, event);
+
+        if self.unhandled_callback.is_none() {
+            self.unhandled_callback = Some(cb);
         }
+    }
 
-        let Some(obj) = self.map.find_mut(object.id().protocol_id()) else {
+    fn dispatch_message_error(&mut self, state: &mut D, error: Error) {
+        let Some(mut cb) = self.message_error_callback.take() else {
             return;
         };
 
-        obj.data = cb;
+        cb(state, self, error);
+
+        if self.message_error_callback.is_none() {
+            self.message_error_callback = Some(cb);
+        }
     }
 
-    pub fn object_data<T: Any>(&self, id: ObjectId) -> Option<&T> {
-        let obj = self.map.find(id.protocol_id())?;
-        let cb = obj.data.as_ref()?;
-        cb.data.downcast_ref()
+    /// Set a callback for messages that could not be dispatched
+    ///
+    /// [`Context::dispatch_pending`], [`Context::blocking_dispatch`] and [`Context::roundtrip`]
+    /// skip such messages and keep dispatching the rest, eg. events of a newer protocol version
+    /// or for unknown objects. Only errors for which [`Error::is_fatal`] is true are returned
+    /// by them. Without a callback the errors are dropped.
+    pub fn set_message_error_callback<F>(&mut self, cb: F)
+    where
+        F: FnMut(&mut D, &mut Self, Error) + 'static,
+    {
+        self.message_error_callback = Some(Box::new(cb));
     }
 
-    pub fn object_data_mut<T: Any>(&mut self, id: ObjectId) -> Option<&mut T> {
-        let obj = self.map.find_mut(id.protocol_id())?;
-        let cb = obj.data.as_mut()?;
-        cb.data.downcast_mut()
+    /// Set a callback for events that no handler is going to see
+    ///
+    /// Events that arrive for an object without a handler are queued until one gets attached,
+    /// this gets called for the ones that are dropped instead: when the object gets removed
+    /// or its queue overflows, or when the handler was attached with a proxy of another type.
+    /// Useful for debugging.
+    pub fn set_unhandled_callback<F>(&mut self, cb: F)
+    where
+        F: FnMut(&mut D, &mut Self, ObjectId, AnyEvent) + 'static,
+    {
+        self.unhandled_callback = Some(Box::new(cb));
+    }
+
+    /// Data attached to the object by its handler
+    ///
+    /// Fails if the object is unknown or if its data is not a `T`.
+    pub fn object_data<T: Any>(&self, id: ObjectId) -> Result<&T> {
+        let obj = self
+            .map
+            .find(id.protocol_id())
+            .ok_or(Error::UnknownObject(id.protocol_id()))?;
+        obj.data
+            .handler
+            .as_ref()
+            .and_then(|handler| handler.data().downcast_ref())
+            .ok_or(Error::ObjectData {
+                object_id: id.protocol_id(),
+                expected: type_name::<T>(),
+            })
     }
 
-    pub fn set_object_callback_with_data<P, T, F>(&mut self, proxy: &P, data: T, mut cb: F)
+    /// Same as [`Context::object_data`], but mutable
+    pub fn object_data_mut<T: Any>(&mut self, id: ObjectId) -> Result<&mut T> {
+        let obj = self
+            .map
+            .find_mut(id.protocol_id())
+            .ok_or(Error::UnknownObject(id.protocol_id()))?;
+        obj.data
+            .handler
+            .as_mut()
+            .and_then(|handler| handler.data_mut().downcast_mut())
+            .ok_or(Error::ObjectData {
+                object_id: id.protocol_id(),
+                expected: type_name::<T>(),
+            })
+    }
+
+    /// Events that arrived before are passed to the handler on the next dispatch
+    fn set_object_handler<P: Proxy>(&mut self, proxy: &P, handler: Box<dyn ObjectHandler<D>>) {
+        let id = proxy.id().protocol_id();
+        let Some(obj) = self.map.find_mut(id) else {
+            return;
+        };
+
+        obj.data.handler = Some(handler);
+        if !obj.data.pending.is_empty() && !self.replay.contains(&id) {
+            self.replay.push(id);
+        }
+    }
+
+    /// Dispatch the events of `proxy` through `D`'s [`Dispatch`] impl, with `data` attached
+    ///
+    /// Replaces any previously set handler.
+    pub fn set_object_dispatch<P, U>(&mut self, proxy: &P, data: U)
     where
-        P: Proxy,
-        P::Event: 'static,
+        P: Proxy + 'static,
+        U: 'static,
+        D: Dispatch<P, U>,
+    {
+        self.set_object_handler(
+            proxy,
+            Box::new(DispatchHandler {
+                data,
+                _proxy: PhantomData::<fn(P)>,
+            }),
+        );
+    }
+
+    pub fn set_object_callback_with_data<P, T, F>(&mut self, proxy: &P, data: T, cb: F)
+    where
+        P: Proxy + 'static,
         F: FnMut(&mut D, &mut Self, &mut T, P, P::Event) + 'static,
         T: 'static,
     {
-        let Some(obj) = self.map.find_mut(proxy.id().protocol_id()) else {
-            return;
-        };
+        self.set_object_handler(
+            proxy,
+            Box::new(CallbackHandler {
+                cb,
+                data,
+                _proxy: PhantomData::<fn(P)>,
+            }),
+        );
+    }
 
-        obj.data = Some(ObjectState {
-            data: Box::new(data),
-            cb: Box::new(
-                move |CallbackArgs {
-                          ctx,
-                          state,
-                          object_id,
-                          object_data,
-                          event,
-                      }: CallbackArgs<D>| {
-                    let event: Box<P::Event> = event.downcast().unwrap();
-                    let data: &mut T = object_data.downcast_mut().unwrap();
-                    cb(state, ctx, data, P::from_id(object_id), *event);
-                },
-            ),
+    /// Observe the events of `proxy`, independently of its callback or [`Dispatch`] handler
+    ///
+    /// Listeners see the events in the order they were added, before the handler gets them.
+    /// Only events arriving after the listener was added are seen, the queued ones are left
+    /// to the handler. The listener is removed when the returned handle is dropped.
+    pub fn add_listener<P, F>(&mut self, proxy: &P, mut cb: F) -> Result<ListenerHandle>
+    where
+        P: Proxy + 'static,
+        F: FnMut(&mut D, &mut Self, P, &P::Event) + 'static,
+    {
+        let id = proxy.id().protocol_id();
+        let obj = self.map.find_mut(id).ok_or(Error::UnknownObject(id))?;
+
+        let alive = Rc::new(Cell::new(true));
+        obj.data.listeners.push(Listener {
+            alive: alive.clone(),
+            cb: Box::new(move |state, ctx, object_id, event| {
+                if let Some(event) = P::Event::from_any_event_ref(event) {
+                    cb(state, ctx, P::from_id(object_id), event);
+                }
+            }),
         });
+
+        Ok(ListenerHandle {
+            alive,
+            object_id: id,
+            dropped: Rc::downgrade(&self.dropped_listeners),
+        })
     }
 
-    pub fn set_object_callback<P, F>(&mut self, proxy: &P, mut cb: F)
+    /// Set a callback for errors the daemon reports about methods sent by `proxy`
+    ///
+    /// Compare [`ProxyError::seq`] with the [`Seq`] returned by a method, to find out which
+    /// of them failed. Errors are emitted as [`pw_core::Event::Error`] on the core as well.
+    pub fn set_error_callback<P, F>(&mut self, proxy: &P, mut cb: F)
     where
         P: Proxy,
-        P::Event: 'static,
+        F: FnMut(&mut D, &mut Self, P, ProxyError) + 'static,
+    {
+        self.error_callbacks.insert(
+            proxy.id().protocol_id(),
+            Box::new(move |state, ctx, id, error| cb(state, ctx, P::from_id(id), error)),
+        );
+    }
+
+    /// Link two ports with the `link-factory`
+    ///
+    /// The state of the link is tracked, see [`Context::link_status`] and
+    /// [`Context::link_settled`]. Errors about creating the link put it in the error state.
+    pub fn create_link(&mut self, spec: LinkSpec) -> Result<PwLink> {
+        let link: PwLink = self.core().create_object(
+            self,
+            pw_core::methods::CreateObject {
+                factory_name: "link-factory".into(),
+                interface: ObjectType::Link.as_interface_name().into(),
+                version: PwLink::VERSION,
+                properties: spec.properties(),
+                new_id: 0,
+            },
+        )?;
+
+        self.links.insert(
+            link.id().protocol_id(),
+            LinkTracker {
+                shared: LinkShared::new(),
+                linger: spec.linger,
+                callback: None,
+            },
+        );
+
+        Ok(link)
+    }
+
+    /// Same as [`Context::create_link`], `cb` is called once the link becomes active or fails
+    pub fn create_link_with_callback<F>(&mut self, spec: LinkSpec, cb: F) -> Result<PwLink>
+    where
+        F: FnOnce(&mut D, &mut Self, PwLink, LinkStatus) + 'static,
+    {
+        let link = self.create_link(spec)?;
+        if let Some(tracker) = self.links.get_mut(&link.id().protocol_id()) {
+            tracker.callback = Some(Box::new(cb));
+        }
+        Ok(link)
+    }
+
+    /// Last known status of a link created with [`Context::create_link`]
+    pub fn link_status(&self, link: &PwLink) -> Option<LinkStatus> {
+        let tracker = self.links.get(&link.id().protocol_id())?;
+        let status = tracker.shared.borrow().status.clone();
+        Some(status)
+    }
+
+    /// Future that resolves once a link created with [`Context::create_link`]
+    /// becomes active or fails
+    pub fn link_settled(&self, link: &PwLink) -> Option<LinkSettled> {
+        let tracker = self.links.get(&link.id().protocol_id())?;
+        Some(LinkSettled {
+            shared: tracker.shared.clone(),
+        })
+    }
+
+    pub fn set_object_callback<P, F>(&mut self, proxy: &P, mut cb: F)
+    where
+        P: Proxy + 'static,
         F: FnMut(&mut D, &mut Self, P, P::Event) + 'static,
     {
         self.set_object_callback_with_data(proxy, (), move |state, ctx, _, proxy, event| {
@@ -0,0 +1,85 @@
+use std::io;
+
+use crate::{context::ProxyError, object_map::ObjectType, protocol::EventDeserializeError};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The daemon closed the connection, eg. because it got restarted
+    #[error("connection closed by the PipeWire daemon")]
+    Disconnected,
+    /// The message header announced more fds than were sent along with it
+    #[error("message expected {expected} fds, but only {received} were received")]
+    MissingFds { expected: u32, received: usize },
+    /// Messages were dispatched from within an event callback
+    #[error("dispatch is not reentrant, it can not be called from an event callback")]
+    NestedDispatch,
+    /// Object id that is not known to the context, or that was destroyed already
+    #[error("unknown object {0}")]
+    UnknownObject(u32),
+    /// The core and the client objects live as long as the connection, they can't be destroyed
+    #[error("object {0} lives as long as the connection, it can not be destroyed")]
+    Singleton(u32),
+    /// The object has no data of the requested type attached, see [`Context::object_data`]
+    ///
+    /// [`Context::object_data`]: crate::context::Context::object_data
+    #[error("object {object_id} has no data of type {expected}")]
+    ObjectData {
+        object_id: u32,
+        expected: &'static str,
+    },
+    /// Event for an object of an interface that we can not dispatch events for yet
+    #[error("events of {0:?} are not supported")]
+    UnsupportedInterface(ObjectType),
+    /// Method or event introduced in a later version than the one the object was bound with
+    #[error("opcode {opcode} of {interface:?} requires version {since}, the object has version {version}")]
+    Version {
+        interface: ObjectType,
+        opcode: u8,
+        since: u32,
+        version: u32,
+    },
+    /// Opcode not known for the interface, eg. an event added in a newer protocol version
+    #[error("unknown opcode {opcode} for {interface}")]
+    UnknownOpcode { interface: &'static str, opcode: u8 },
+    #[error(transparent)]
+    Deserialize(#[from] EventDeserializeError),
+    /// The daemon rejected a method, see [`ProxyError`]
+    #[error("method failed with {}: {}", .0.res, .0.message)]
+    Proxy(ProxyError),
+    /// Param passed to a `set_param` method is not a spa object
+    #[error("invalid param: {0}")]
+    InvalidParam(pod::DeserializeError),
+    /// Malformed v0 `UpdateTypes` message, see [`TypeMap`](crate::type_map::TypeMap)
+    #[error("v0 type map: {0}")]
+    TypeMap(pod::DeserializeError),
+    /// Malformed message footer
+    #[error("footer: {0}")]
+    Footer(pod::DeserializeError),
+    #[error(transparent)]
+    Io(io::Error),
+}
+
+impl Error {
+    /// The operation could not complete without blocking, try again once the socket is ready
+    pub fn is_would_block(&self) -> bool {
+        matches!(self, Self::Io(err) if err.kind() == io::ErrorKind::WouldBlock)
+    }
+
+    /// The connection is unusable, as opposed to a single message that could not be handled
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Self::Disconnected | Self::Io(_))
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionReset => Self::Disconnected,
+            _ => Self::Io(err),
+        }
+    }
+}
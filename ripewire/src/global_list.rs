@@ -14,6 +14,9 @@ impl GlobalList {
             pw_registry::Event::GlobalRemove(_) => {
                 //
             }
+            pw_registry::Event::Removed => {
+                self.globals.clear();
+            }
         }
     }
 
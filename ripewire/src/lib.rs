@@ -1,10 +1,15 @@
 pub mod connection;
 pub mod context;
+pub mod error;
 pub mod global_list;
+pub mod link;
 pub mod memory_registry;
 pub mod object_map;
+pub mod params;
 pub mod protocol;
 pub mod proxy;
+pub mod spa_json;
+pub mod type_map;
 
 pub mod reexports {
     pub use libc;
@@ -12,7 +17,9 @@ pub mod reexports {
     pub use pod;
 }
 
-use std::os::fd::RawFd;
+use std::{io, os::fd::RawFd};
+
+pub use error::{Error, Result};
 
 use protocol::PwDictionary;
 
@@ -32,17 +39,34 @@ pub fn set_blocking(fd: RawFd, blocking: bool) {
     let _ = unsafe { libc::fcntl(fd, libc::F_SETFL, flags) != -1 };
 }
 
-pub fn poll(fd: RawFd, timeout: i32) {
+/// Wait for `fd` to become readable, `timeout` is in milliseconds, `-1` waits forever
+///
+/// Returns `false` if the timeout expired.
+pub fn poll(fd: RawFd, timeout: i32) -> io::Result<bool> {
+    poll_events(fd, libc::POLLIN, timeout)
+}
+
+fn poll_events(fd: RawFd, events: i16, timeout: i32) -> io::Result<bool> {
     let fd = libc::pollfd {
         fd,
-        events: libc::POLLIN,
+        events,
         revents: 0,
     };
 
     let mut fds = [fd];
 
-    unsafe {
-        libc::poll(fds.as_mut_ptr(), fds.len() as u64, timeout);
+    loop {
+        let res = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout) };
+        match res {
+            -1 => {
+                let err = io::Error::last_os_error();
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            0 => return Ok(false),
+            _ => return Ok(true),
+        }
     }
 }
 
@@ -0,0 +1,160 @@
+//! Links created through the `link-factory`, see [`Context::create_link`]
+//!
+//! [`Context::create_link`]: crate::context::Context::create_link
+
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context as TaskContext, Poll, Waker},
+};
+
+use libspa_consts::{PwLinkState, SpaEnum};
+
+use crate::protocol::PwDictionary;
+
+/// Ports to link and how
+///
+/// The `link-factory` looks the ports up through their nodes, so both are needed.
+#[derive(Debug, Clone)]
+pub struct LinkSpec {
+    /// Global id of the node of the output port
+    pub output_node: u32,
+    /// Global id of the output port
+    pub output_port: u32,
+    /// Global id of the node of the input port
+    pub input_node: u32,
+    /// Global id of the input port
+    pub input_port: u32,
+    /// The link doesn't keep the nodes running on its own
+    pub passive: bool,
+    /// Keep the link object when it fails, otherwise it gets destroyed
+    /// once it ends up in the error state
+    pub linger: bool,
+    /// Keep the link after the client disconnects or destroys the proxy,
+    /// sets `object.linger`
+    pub object_linger: bool,
+}
+
+impl LinkSpec {
+    pub fn new(output_node: u32, output_port: u32, input_node: u32, input_port: u32) -> Self {
+        Self {
+            output_node,
+            output_port,
+            input_node,
+            input_port,
+            passive: false,
+            linger: false,
+            object_linger: false,
+        }
+    }
+
+    /// Properties for the `link-factory`
+    pub fn properties(&self) -> PwDictionary {
+        let mut properties = PwDictionary::new();
+        properties.insert("link.output.node".into(), self.output_node.to_string());
+        properties.insert("link.output.port".into(), self.output_port.to_string());
+        properties.insert("link.input.node".into(), self.input_node.to_string());
+        properties.insert("link.input.port".into(), self.input_port.to_string());
+        if self.passive {
+            properties.insert("link.passive".into(), "true".into());
+        }
+        if self.object_linger {
+            properties.insert("object.linger".into(), "true".into());
+        }
+        properties
+    }
+}
+
+/// Last known state of a link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkStatus {
+    pub state: SpaEnum<PwLinkState, i32>,
+    /// Why the link failed, in the error state
+    pub error: Option<String>,
+}
+
+impl LinkStatus {
+    fn new() -> Self {
+        Self {
+            state: SpaEnum::Value(PwLinkState::Init),
+            error: None,
+        }
+    }
+
+    /// The link is up or failed
+    ///
+    /// Links between nodes that are not running stay paused and only become active
+    /// once the nodes start.
+    pub fn is_settled(&self) -> bool {
+        matches!(
+            self.state,
+            SpaEnum::Value(PwLinkState::Active | PwLinkState::Error)
+        )
+    }
+}
+
+/// Status shared with the [`LinkSettled`] futures
+#[derive(Debug)]
+pub(crate) struct LinkShared {
+    pub(crate) status: LinkStatus,
+    pub(crate) settled: bool,
+    wakers: Vec<Waker>,
+}
+
+impl LinkShared {
+    pub(crate) fn new() -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            status: LinkStatus::new(),
+            settled: false,
+            wakers: Vec::new(),
+        }))
+    }
+
+    /// Returns true the first time the link settles
+    pub(crate) fn update(&mut self, status: LinkStatus) -> bool {
+        let settles = !self.settled && status.is_settled();
+        self.status = status;
+
+        if settles {
+            self.settled = true;
+            for waker in self.wakers.drain(..) {
+                waker.wake();
+            }
+        }
+
+        settles
+    }
+}
+
+/// Resolves once the link becomes active or fails, see [`Context::link_settled`]
+///
+/// Only makes progress while the context is being dispatched.
+///
+/// [`Context::link_settled`]: crate::context::Context::link_settled
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct LinkSettled {
+    pub(crate) shared: Rc<RefCell<LinkShared>>,
+}
+
+impl Future for LinkSettled {
+    type Output = LinkStatus;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<LinkStatus> {
+        let mut shared = self.shared.borrow_mut();
+        if shared.settled {
+            return Poll::Ready(shared.status.clone());
+        }
+
+        if !shared
+            .wakers
+            .iter()
+            .any(|waker| waker.will_wake(cx.waker()))
+        {
+            shared.wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
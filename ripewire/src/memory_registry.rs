@@ -1,7 +1,4 @@
-use std::{
-    collections::HashMap,
-    os::fd::{FromRawFd, OwnedFd},
-};
+use std::{collections::HashMap, os::fd::OwnedFd};
 
 use libspa_consts::{SpaDataType, SpaEnum};
 
@@ -36,8 +33,11 @@ impl MemoryRegistry {
         }
     }
 
-    pub fn add_mem(&mut self, add_mem: &pw_core::events::AddMem) {
-        let fd = add_mem.fd.fd.unwrap();
+    /// Takes the fd out of the event, events without a valid fd are ignored
+    pub fn add_mem(&mut self, add_mem: &mut pw_core::events::AddMem) {
+        let Some(fd) = add_mem.fd.fd.take() else {
+            return;
+        };
 
         let mem_type = add_mem.ty;
 
@@ -47,7 +47,7 @@ impl MemoryRegistry {
                 id: add_mem.id,
                 mem_type,
                 flags: add_mem.flags,
-                fd: unsafe { OwnedFd::from_raw_fd(fd) },
+                fd,
             },
         );
     }
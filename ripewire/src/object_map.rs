@@ -21,6 +21,7 @@ pub enum ObjectType {
     Port,
     Profiler,
     Registry,
+    SecurityContext,
     Session,
     Other(String),
 }
@@ -45,6 +46,7 @@ impl ObjectType {
             "PipeWire:Interface:Port" => Self::Port,
             "PipeWire:Interface:Profiler" => Self::Profiler,
             "PipeWire:Interface:Registry" => Self::Registry,
+            "PipeWire:Interface:SecurityContext" => Self::SecurityContext,
             "PipeWire:Interface:Session" => Self::Session,
             _ => Self::Other(name.to_string()),
         }
@@ -69,6 +71,7 @@ impl ObjectType {
             Self::Port => "PipeWire:Interface:Port",
             Self::Profiler => "PipeWire:Interface:Profiler",
             Self::Registry => "PipeWire:Interface:Registry",
+            Self::SecurityContext => "PipeWire:Interface:SecurityContext",
             Self::Session => "PipeWire:Interface:Session",
             Self::Other(name) => name,
         }
@@ -82,6 +85,8 @@ pub struct Object<Data> {
     pub interface: ObjectType,
     /// Version of this object
     pub version: u32,
+    /// Id of the global this object is bound to, as announced by the server
+    pub global_id: Option<u32>,
     /// ObjectData associated to this object (ex: its event queue client side)
     pub data: Data,
 }
@@ -0,0 +1,331 @@
+//! Param enumeration correlated by seq, see [`ParamsProxy`]
+
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context as TaskContext, Poll, Waker},
+};
+
+use libspa_consts::SpaParamType;
+use pod::{deserialize::OwnedPod, Id};
+
+use crate::{
+    context::{Context, ListenerHandle, ProxyError},
+    protocol::{
+        pw_core, pw_device, pw_endpoint, pw_endpoint_link, pw_endpoint_stream, pw_node, pw_port,
+        pw_session, MethodSerialize,
+    },
+    proxy::{
+        Proxy, PwDevice, PwEndpoint, PwEndpointLink, PwEndpointStream, PwNode, PwPort, PwSession,
+        Seq,
+    },
+    Result,
+};
+
+/// Which params to enumerate
+#[derive(Debug, Clone)]
+pub struct ParamQuery {
+    pub id: SpaParamType,
+    /// Index of the first param
+    pub index: u32,
+    /// Maximum number of params, 0 for all of them
+    pub num: u32,
+    /// Only params that intersect with this object are returned
+    pub filter: Option<pod::serialize::OwnedPod>,
+}
+
+impl ParamQuery {
+    pub fn new(id: SpaParamType) -> Self {
+        Self {
+            id,
+            index: 0,
+            num: 0,
+            filter: None,
+        }
+    }
+
+    fn raw_id(&self) -> Id {
+        Id(self.id as u32)
+    }
+
+    /// The filter, or a None pod
+    fn filter_pod(&self) -> pod::serialize::OwnedPod {
+        match &self.filter {
+            Some(filter) => filter.clone(),
+            None => pod::Builder::with(|b| {
+                b.write_none();
+            }),
+        }
+    }
+}
+
+/// Params returned for a [`ParamQuery`]
+#[derive(Debug, Clone)]
+pub struct ParamList {
+    /// Ordered by index
+    pub params: Vec<OwnedPod>,
+    /// Index to continue from, when `num` params were returned and there might be more
+    pub next: Option<u32>,
+}
+
+mod sealed {
+    use super::*;
+
+    /// A `Param` event, as every interface with params emits it
+    pub struct ParamEvent<'a> {
+        pub seq: i32,
+        pub index: u32,
+        pub next: u32,
+        pub param: &'a OwnedPod,
+    }
+
+    /// `EnumParams` method and `Param` event of the interface
+    pub trait ParamsInterface: Proxy + Sized {
+        type EnumParams: MethodSerialize;
+
+        fn enum_params_method(query: &ParamQuery, seq: Seq) -> Self::EnumParams;
+        fn param_event(event: &Self::Event) -> Option<ParamEvent<'_>>;
+    }
+}
+
+use sealed::{ParamEvent, ParamsInterface};
+
+macro_rules! params_interface {
+    ($($proxy:ty => $interface:ident),*) => {
+        $(
+            impl ParamsInterface for $proxy {
+                type EnumParams = $interface::methods::EnumParams;
+
+                fn enum_params_method(query: &ParamQuery, seq: Seq) -> Self::EnumParams {
+                    $interface::methods::EnumParams {
+                        seq: seq.raw() as i32,
+                        id: query.raw_id(),
+                        index: query.index,
+                        num: query.num,
+                        filter: query.filter_pod(),
+                    }
+                }
+
+                fn param_event(event: &Self::Event) -> Option<ParamEvent<'_>> {
+                    match event {
+                        $interface::Event::Param(event) => Some(ParamEvent {
+                            seq: event.seq,
+                            index: event.index,
+                            next: event.next,
+                            param: &event.params,
+                        }),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+params_interface!(
+    PwNode => pw_node,
+    PwDevice => pw_device,
+    PwPort => pw_port,
+    PwSession => pw_session,
+    PwEndpoint => pw_endpoint,
+    PwEndpointStream => pw_endpoint_stream,
+    PwEndpointLink => pw_endpoint_link
+);
+
+/// Proxies that can enumerate their params
+///
+/// Every enumeration gets its own seq, the `Param` events of other enumerations are told apart
+/// by it.
+pub trait ParamsProxy: ParamsInterface + 'static {
+    /// Enumerate the params selected by `query`, the `Param` events carry the returned seq
+    fn enum_params_query<D>(&self, context: &mut Context<D>, query: &ParamQuery) -> Result<Seq> {
+        let seq = context.next_seq();
+        context.send_method(&self.id(), &Self::enum_params_method(query, seq))
+    }
+
+    /// Enumerate the params selected by `query`, `cb` gets them once all of them arrived
+    ///
+    /// The daemon rejecting the enumeration, eg. for lack of permissions, is passed to `cb`
+    /// as well. Returns the seq of the enumeration.
+    fn collect_params<D, F>(
+        &self,
+        context: &mut Context<D>,
+        query: &ParamQuery,
+        cb: F,
+    ) -> Result<Seq>
+    where
+        F: FnOnce(&mut D, &mut Context<D>, Self, Result<ParamList, ProxyError>) + 'static,
+    {
+        let (seq, _) = collect_params(self, context, query, cb)?;
+        Ok(seq)
+    }
+
+    /// Same as [`ParamsProxy::collect_params`], as a future
+    fn collect_params_future<D>(
+        &self,
+        context: &mut Context<D>,
+        query: &ParamQuery,
+    ) -> Result<ParamsCollected> {
+        let (_, shared) = collect_params(self, context, query, |_, _, _, _| {})?;
+        Ok(ParamsCollected { shared })
+    }
+}
+
+impl<P: ParamsInterface + 'static> ParamsProxy for P {}
+
+/// Send the enumeration of `query` with a fresh seq and collect the `Param` events carrying it,
+/// until the `Done` of a sync sent right after, or an error about the enumeration
+fn collect_params<D, P, F>(
+    proxy: &P,
+    context: &mut Context<D>,
+    query: &ParamQuery,
+    cb: F,
+) -> Result<(Seq, Rc<RefCell<ParamsShared>>)>
+where
+    P: ParamsInterface + 'static,
+    F: FnOnce(&mut D, &mut Context<D>, P, Result<ParamList, ProxyError>) + 'static,
+{
+    let seq = context.next_seq();
+    let shared = ParamsShared::new(query.num);
+
+    let object_listener = context.add_listener(proxy, {
+        let shared = shared.clone();
+        move |_, _, _, event: &P::Event| {
+            if let Some(event) = P::param_event(event) {
+                if event.seq as u32 == seq.raw() {
+                    shared.borrow_mut().push(event);
+                }
+            }
+        }
+    })?;
+    context.send_method(&proxy.id(), &P::enum_params_method(query, seq))?;
+
+    // The daemon answers the sync once it emitted all params
+    let object_id = proxy.id();
+    let sync_seq = context.next_seq();
+    let core = context.core();
+    let mut object_listener = Some(object_listener);
+    let mut cb = Some(cb);
+    let core_listener: Rc<RefCell<Option<ListenerHandle>>> = Rc::default();
+
+    let handle = context.add_listener(&core, {
+        let core_listener = core_listener.clone();
+        let shared = shared.clone();
+        let object_id = object_id.clone();
+        move |state, ctx, _, event| {
+            let error = match event {
+                pw_core::Event::Done(done)
+                    if done.id == Some(object_id.protocol_id())
+                        && done.seq as u32 == sync_seq.raw() =>
+                {
+                    None
+                }
+                pw_core::Event::Error(error)
+                    if error.id == object_id.protocol_id() && error.seq as u32 == seq.raw() =>
+                {
+                    Some(ProxyError {
+                        seq,
+                        res: error.res,
+                        message: error.message.clone(),
+                    })
+                }
+                _ => return,
+            };
+
+            object_listener.take();
+            core_listener.borrow_mut().take();
+            let result = shared.borrow_mut().finish(error);
+            if let Some(cb) = cb.take() {
+                cb(state, ctx, P::from_id(object_id.clone()), result);
+            }
+        }
+    })?;
+    *core_listener.borrow_mut() = Some(handle);
+
+    core.sync(context, object_id.protocol_id(), sync_seq.raw())?;
+    Ok((seq, shared))
+}
+
+/// Params collected so far, shared with the [`ParamsCollected`] futures
+#[derive(Debug)]
+struct ParamsShared {
+    num: u32,
+    /// `(index, next, param)`
+    collected: Vec<(u32, u32, OwnedPod)>,
+    result: Option<Result<ParamList, ProxyError>>,
+    wakers: Vec<Waker>,
+}
+
+impl ParamsShared {
+    fn new(num: u32) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            num,
+            collected: Vec::new(),
+            result: None,
+            wakers: Vec::new(),
+        }))
+    }
+
+    fn push(&mut self, event: ParamEvent) {
+        self.collected
+            .push((event.index, event.next, event.param.clone()));
+    }
+
+    /// Called once the sync following the enumeration is done, or the enumeration failed
+    fn finish(&mut self, error: Option<ProxyError>) -> Result<ParamList, ProxyError> {
+        let mut collected = std::mem::take(&mut self.collected);
+        collected.sort_by_key(|(index, _, _)| *index);
+
+        let next = match collected.last() {
+            Some((_, next, _)) if self.num != 0 && collected.len() as u32 >= self.num => {
+                Some(*next)
+            }
+            _ => None,
+        };
+        let result = match error {
+            Some(error) => Err(error),
+            None => Ok(ParamList {
+                params: collected.into_iter().map(|(_, _, param)| param).collect(),
+                next,
+            }),
+        };
+
+        self.result = Some(result.clone());
+        for waker in self.wakers.drain(..) {
+            waker.wake();
+        }
+
+        result
+    }
+}
+
+/// Resolves once all params of an enumeration arrived, see [`ParamsProxy::collect_params_future`]
+///
+/// Only makes progress while the context is being dispatched.
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct ParamsCollected {
+    shared: Rc<RefCell<ParamsShared>>,
+}
+
+impl Future for ParamsCollected {
+    type Output = Result<ParamList, ProxyError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.borrow_mut();
+        if let Some(result) = shared.result.as_ref() {
+            return Poll::Ready(result.clone());
+        }
+
+        if !shared
+            .wakers
+            .iter()
+            .any(|waker| waker.will_wake(cx.waker()))
+        {
+            shared.wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(unused_variables)]
 
-use crate::object_map::ObjectType;
+use crate::{connection::FooterClient, object_map::ObjectType};
 use libspa_consts::{SpaDataType, SpaEnum, SpaIoType, SpaMetaType, SpaParamType};
 use pod::{
     deserialize::{OwnedPod, PodStructDeserializer},
@@ -9,22 +9,26 @@ use pod::{
 use std::{
     collections::HashMap,
     io::{Seek, Write},
-    os::fd::RawFd,
+    os::fd::{BorrowedFd, OwnedFd},
 };
 
 pub trait MethodSerialize: Sized {
     const OPCODE: u8;
-    fn serialize(&self, buf: impl Write + Seek, fds: &mut Vec<RawFd>);
+    /// Interface version that introduced the method
+    const SINCE: u32 = 0;
+    fn serialize<'a>(&'a self, buf: impl Write + Seek, fds: &mut Vec<BorrowedFd<'a>>);
 }
 
 trait MethodSerializeSimple: Sized {
     const OPCODE: u8;
+    const SINCE: u32 = 0;
     fn serialize_simple(&self, buf: impl Write + Seek);
 }
 
 impl<T: MethodSerializeSimple> MethodSerialize for T {
     const OPCODE: u8 = T::OPCODE;
-    fn serialize(&self, buf: impl Write + Seek, _fds: &mut Vec<RawFd>) {
+    const SINCE: u32 = T::SINCE;
+    fn serialize<'a>(&'a self, buf: impl Write + Seek, _fds: &mut Vec<BorrowedFd<'a>>) {
         T::serialize_simple(self, buf);
     }
 }
@@ -47,28 +51,46 @@ pub trait HasInterface {
 
 trait EventDeserialize: Sized {
     const OPCODE: u8;
+    /// Interface version that introduced the event
+    const SINCE: u32 = 0;
     fn deserialize(
         deserializer: &mut PodDeserializer,
-        fds: &[RawFd],
+        fds: &mut [Option<OwnedFd>],
     ) -> pod::deserialize::Result<Self>;
 }
 
 impl<T: EventDeserialize> Deserialize for T {
     fn deserialize(
         deserializer: &mut PodDeserializer,
-        fds: &[RawFd],
+        fds: &mut [Option<OwnedFd>],
     ) -> pod::deserialize::Result<Self> {
         <T as EventDeserialize>::deserialize(deserializer, fds)
     }
 }
 
+/// Deserialize a message body
+///
+/// `fds` are the fds received along with the message, deserialized values take the ones
+/// they reference, the rest gets closed when the message is dropped.
 pub trait Deserialize: Sized {
     fn deserialize(
         deserializer: &mut PodDeserializer,
-        fds: &[RawFd],
+        fds: &mut [Option<OwnedFd>],
     ) -> pod::deserialize::Result<Self>;
 }
 
+fn parse_fd(
+    pod: &mut PodStructDeserializer,
+    fds: &mut [Option<OwnedFd>],
+) -> pod::deserialize::Result<Fd> {
+    let id = pod.pop_field()?.as_fd()?;
+    let fd = usize::try_from(id)
+        .ok()
+        .and_then(|id| fds.get_mut(id))
+        .and_then(Option::take);
+    Ok(Fd { id, fd })
+}
+
 // It's easier to debug whenever keys are ordered
 // pub type PwDictionary = BTreeMap<String, String>;
 pub type PwDictionary = HashMap<String, String>;
@@ -127,16 +149,245 @@ fn parse_params(pod: &mut PodStructDeserializer) -> pod::deserialize::Result<Vec
     Ok(params)
 }
 
+fn write_dict(b: &mut pod::Builder<impl Write + Seek>, dict: &PwDictionary) {
+    b.push_struct_with(|b| {
+        b.write_u32(dict.len() as u32);
+        for (key, value) in dict.iter() {
+            b.write_str(key);
+            b.write_str(value);
+        }
+    });
+}
+
+fn write_params(b: &mut pod::Builder<impl Write + Seek>, params: &[ParamInfo]) {
+    b.push_struct_with(|b| {
+        b.write_u32(params.len() as u32);
+        for ParamInfo { id, flags } in params.iter() {
+            b.write_id(id.as_raw());
+            b.write_u32(flags.bits());
+        }
+    });
+}
+
 pub mod pw_client;
+pub mod pw_client_endpoint;
 pub mod pw_client_node;
+pub mod pw_client_session;
 pub mod pw_core;
 pub mod pw_device;
+pub mod pw_endpoint;
+pub mod pw_endpoint_link;
+pub mod pw_endpoint_stream;
 pub mod pw_factory;
 pub mod pw_link;
+pub mod pw_metadata;
 pub mod pw_module;
 pub mod pw_node;
 pub mod pw_port;
+pub mod pw_profiler;
 pub mod pw_registry;
+pub mod pw_security_context;
+pub mod pw_session;
+
+/// Event of any of the supported interfaces
+///
+/// Lets [`Context`](crate::context::Context) hand events around without boxing them,
+/// convert back with `TryFrom`.
+#[derive(Debug)]
+pub enum AnyEvent {
+    Client(pw_client::Event),
+    ClientEndpoint(pw_client_endpoint::Event),
+    ClientNode(pw_client_node::Event),
+    ClientSession(pw_client_session::Event),
+    Core(pw_core::Event),
+    Device(pw_device::Event),
+    Endpoint(pw_endpoint::Event),
+    EndpointLink(pw_endpoint_link::Event),
+    EndpointStream(pw_endpoint_stream::Event),
+    Factory(pw_factory::Event),
+    Link(pw_link::Event),
+    Metadata(pw_metadata::Event),
+    Module(pw_module::Event),
+    Node(pw_node::Event),
+    Port(pw_port::Event),
+    Profiler(pw_profiler::Event),
+    Registry(pw_registry::Event),
+    SecurityContext(pw_security_context::Event),
+    Session(pw_session::Event),
+}
+
+impl AnyEvent {
+    /// Deserialize the event `opcode` of an object of type `ty`, bound with `version`
+    ///
+    /// Events introduced in a later version of the interface are rejected.
+    pub fn deserialize(
+        ty: &ObjectType,
+        version: u32,
+        opcode: u8,
+        pod: &mut PodDeserializer,
+        fds: &mut [Option<OwnedFd>],
+    ) -> crate::Result<Self> {
+        let since = match ty {
+            ObjectType::Client => pw_client::Event::since(opcode),
+            ObjectType::ClientEndpoint => pw_client_endpoint::Event::since(opcode),
+            ObjectType::ClientNode => pw_client_node::Event::since(opcode),
+            ObjectType::ClientSession => pw_client_session::Event::since(opcode),
+            ObjectType::Core => pw_core::Event::since(opcode),
+            ObjectType::Device => pw_device::Event::since(opcode),
+            ObjectType::Endpoint => pw_endpoint::Event::since(opcode),
+            ObjectType::EndpointLink => pw_endpoint_link::Event::since(opcode),
+            ObjectType::EndpointStream => pw_endpoint_stream::Event::since(opcode),
+            ObjectType::Factory => pw_factory::Event::since(opcode),
+            ObjectType::Link => pw_link::Event::since(opcode),
+            ObjectType::Metadata => pw_metadata::Event::since(opcode),
+            ObjectType::Module => pw_module::Event::since(opcode),
+            ObjectType::Node => pw_node::Event::since(opcode),
+            ObjectType::Port => pw_port::Event::since(opcode),
+            ObjectType::Profiler => pw_profiler::Event::since(opcode),
+            ObjectType::Registry => pw_registry::Event::since(opcode),
+            ObjectType::SecurityContext => pw_security_context::Event::since(opcode),
+            ObjectType::Session => pw_session::Event::since(opcode),
+            _ => 0,
+        };
+        if since > version {
+            return Err(crate::Error::Version {
+                interface: ty.clone(),
+                opcode,
+                since,
+                version,
+            });
+        }
+
+        Ok(match ty {
+            ObjectType::Client => Self::Client(pw_client::Event::deserialize(opcode, pod, fds)?),
+            ObjectType::ClientEndpoint => {
+                Self::ClientEndpoint(pw_client_endpoint::Event::deserialize(opcode, pod, fds)?)
+            }
+            ObjectType::ClientNode => {
+                Self::ClientNode(pw_client_node::Event::deserialize(opcode, pod, fds)?)
+            }
+            ObjectType::ClientSession => {
+                Self::ClientSession(pw_client_session::Event::deserialize(opcode, pod, fds)?)
+            }
+            ObjectType::Core => Self::Core(pw_core::Event::deserialize(opcode, pod, fds)?),
+            ObjectType::Device => Self::Device(pw_device::Event::deserialize(opcode, pod, fds)?),
+            ObjectType::Endpoint => {
+                Self::Endpoint(pw_endpoint::Event::deserialize(opcode, pod, fds)?)
+            }
+            ObjectType::EndpointLink => {
+                Self::EndpointLink(pw_endpoint_link::Event::deserialize(opcode, pod, fds)?)
+            }
+            ObjectType::EndpointStream => {
+                Self::EndpointStream(pw_endpoint_stream::Event::deserialize(opcode, pod, fds)?)
+            }
+            ObjectType::Factory => Self::Factory(pw_factory::Event::deserialize(opcode, pod, fds)?),
+            ObjectType::Link => Self::Link(pw_link::Event::deserialize(opcode, pod, fds)?),
+            ObjectType::Metadata => {
+                Self::Metadata(pw_metadata::Event::deserialize(opcode, pod, fds)?)
+            }
+            ObjectType::Module => Self::Module(pw_module::Event::deserialize(opcode, pod, fds)?),
+            ObjectType::Node => Self::Node(pw_node::Event::deserialize(opcode, pod, fds)?),
+            ObjectType::Port => Self::Port(pw_port::Event::deserialize(opcode, pod, fds)?),
+            ObjectType::Profiler => {
+                Self::Profiler(pw_profiler::Event::deserialize(opcode, pod, fds)?)
+            }
+            ObjectType::Registry => {
+                Self::Registry(pw_registry::Event::deserialize(opcode, pod, fds)?)
+            }
+            ObjectType::SecurityContext => {
+                Self::SecurityContext(pw_security_context::Event::deserialize(opcode, pod, fds)?)
+            }
+            ObjectType::Session => Self::Session(pw_session::Event::deserialize(opcode, pod, fds)?),
+            ty => return Err(crate::Error::UnsupportedInterface(ty.clone())),
+        })
+    }
+
+    /// The `Removed` pseudo event of an object of type `ty`, the core can't be removed
+    pub fn removed(ty: &ObjectType) -> Option<Self> {
+        Some(match ty {
+            ObjectType::Client => Self::Client(pw_client::Event::Removed),
+            ObjectType::ClientEndpoint => Self::ClientEndpoint(pw_client_endpoint::Event::Removed),
+            ObjectType::ClientNode => Self::ClientNode(pw_client_node::Event::Removed),
+            ObjectType::ClientSession => Self::ClientSession(pw_client_session::Event::Removed),
+            ObjectType::Device => Self::Device(pw_device::Event::Removed),
+            ObjectType::Endpoint => Self::Endpoint(pw_endpoint::Event::Removed),
+            ObjectType::EndpointLink => Self::EndpointLink(pw_endpoint_link::Event::Removed),
+            ObjectType::EndpointStream => Self::EndpointStream(pw_endpoint_stream::Event::Removed),
+            ObjectType::Factory => Self::Factory(pw_factory::Event::Removed),
+            ObjectType::Link => Self::Link(pw_link::Event::Removed),
+            ObjectType::Metadata => Self::Metadata(pw_metadata::Event::Removed),
+            ObjectType::Module => Self::Module(pw_module::Event::Removed),
+            ObjectType::Node => Self::Node(pw_node::Event::Removed),
+            ObjectType::Port => Self::Port(pw_port::Event::Removed),
+            ObjectType::Profiler => Self::Profiler(pw_profiler::Event::Removed),
+            ObjectType::Registry => Self::Registry(pw_registry::Event::Removed),
+            ObjectType::SecurityContext => {
+                Self::SecurityContext(pw_security_context::Event::Removed)
+            }
+            ObjectType::Session => Self::Session(pw_session::Event::Removed),
+            _ => return None,
+        })
+    }
+}
+
+/// Borrow the event of a specific interface out of an [`AnyEvent`]
+pub trait FromAnyEventRef {
+    fn from_any_event_ref(event: &AnyEvent) -> Option<&Self>;
+}
+
+macro_rules! any_event {
+    ($($variant:ident => $module:ident,)*) => {
+        $(
+            impl From<$module::Event> for AnyEvent {
+                fn from(event: $module::Event) -> Self {
+                    Self::$variant(event)
+                }
+            }
+
+            impl TryFrom<AnyEvent> for $module::Event {
+                type Error = AnyEvent;
+
+                fn try_from(event: AnyEvent) -> Result<Self, AnyEvent> {
+                    match event {
+                        AnyEvent::$variant(event) => Ok(event),
+                        event => Err(event),
+                    }
+                }
+            }
+
+            impl FromAnyEventRef for $module::Event {
+                fn from_any_event_ref(event: &AnyEvent) -> Option<&Self> {
+                    match event {
+                        AnyEvent::$variant(event) => Some(event),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+any_event! {
+    Client => pw_client,
+    ClientEndpoint => pw_client_endpoint,
+    ClientNode => pw_client_node,
+    ClientSession => pw_client_session,
+    Core => pw_core,
+    Device => pw_device,
+    Endpoint => pw_endpoint,
+    EndpointLink => pw_endpoint_link,
+    EndpointStream => pw_endpoint_stream,
+    Factory => pw_factory,
+    Link => pw_link,
+    Metadata => pw_metadata,
+    Module => pw_module,
+    Node => pw_node,
+    Port => pw_port,
+    Profiler => pw_profiler,
+    Registry => pw_registry,
+    SecurityContext => pw_security_context,
+    Session => pw_session,
+}
 
 pub fn create_msg<MSG>(object_id: u32, value: &MSG) -> Vec<u8>
 where
@@ -145,7 +396,19 @@ where
     create_msg_with_fds(object_id, value).0
 }
 
-pub fn create_msg_with_fds<MSG>(object_id: u32, value: &MSG) -> (Vec<u8>, Vec<RawFd>)
+pub fn create_msg_with_fds<MSG>(object_id: u32, value: &MSG) -> (Vec<u8>, Vec<BorrowedFd<'_>>)
+where
+    MSG: MethodSerialize,
+{
+    create_msg_with_footers(object_id, value, &[])
+}
+
+/// Same as [`create_msg_with_fds`], with `footers` appended after the body
+pub fn create_msg_with_footers<'a, MSG>(
+    object_id: u32,
+    value: &'a MSG,
+    footers: &[FooterClient],
+) -> (Vec<u8>, Vec<BorrowedFd<'a>>)
 where
     MSG: MethodSerialize,
 {
@@ -154,6 +417,9 @@ where
     value.serialize(&mut buff, &mut fds);
 
     let mut pod = buff.into_inner();
+    if !footers.is_empty() {
+        pod.append(&mut FooterClient::serialize(footers));
+    }
 
     let header = crate::connection::Header {
         object_id,
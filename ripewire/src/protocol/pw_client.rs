@@ -3,11 +3,18 @@
 //! The currently connected client always has the Client object with proxy id 1.
 
 use super::*;
+use std::collections::BTreeMap;
 
 pub const OBJECT_ID: u32 = 1;
 
+/// Id of the default permission entry, it applies to globals without an entry of their own
+pub const ID_ANY: u32 = u32::MAX;
+
+/// Global id of the core
+pub const ID_CORE: u32 = 0;
+
 bitflags::bitflags! {
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub struct PermissionFlags: u32 {
         /// object can be seen and events can be received
         const R = 0o400;
@@ -21,17 +28,124 @@ bitflags::bitflags! {
         /// a link can be made between a node that doesn't have
         /// permission to see the other node, Since 0.3.77
         const L = 0o020;
+
+        /// Objects can be seen and used, but not modified
+        const RX = Self::R.bits() | Self::X.bits();
+        const RWX = Self::R.bits() | Self::W.bits() | Self::X.bits();
+        /// What unrestricted clients get
+        const ALL = Self::RWX.bits() | Self::M.bits();
     }
 }
 
+/// Marks unused entries of the permission table reported by the daemon
+const PERMISSIONS_INVALID: u32 = u32::MAX;
+
 #[derive(Debug, Clone)]
 pub struct Permission {
-    /// The global id
+    /// The global id, or [`ID_ANY`] for the default entry
     pub id: u32,
     /// The permissions for the global id
     pub permissions: PermissionFlags,
 }
 
+impl Permission {
+    pub fn new(id: u32, permissions: PermissionFlags) -> Self {
+        Self { id, permissions }
+    }
+}
+
+/// Permissions of a client on the globals, see [`PwClient::collect_permissions`]
+///
+/// [`PwClient::collect_permissions`]: crate::proxy::PwClient::collect_permissions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PermissionTable {
+    /// The [`ID_ANY`] entry, used for globals without an entry of their own
+    pub default: Option<PermissionFlags>,
+    entries: BTreeMap<u32, PermissionFlags>,
+}
+
+impl PermissionTable {
+    pub fn new(default: PermissionFlags) -> Self {
+        Self {
+            default: Some(default),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Everything can be seen and used but nothing modified, for restricted clients
+    pub fn restricted() -> Self {
+        Self::new(PermissionFlags::RX)
+    }
+
+    /// Only the core can be seen, for sandboxed clients that get objects granted one by one
+    pub fn sandboxed() -> Self {
+        let mut table = Self::new(PermissionFlags::empty());
+        table.set(ID_CORE, PermissionFlags::RX);
+        table
+    }
+
+    /// Build the table from the entries reported by the daemon
+    pub fn from_permissions(permissions: impl IntoIterator<Item = Permission>) -> Self {
+        let mut table = Self::default();
+        for Permission { id, permissions } in permissions {
+            if permissions.bits() == PERMISSIONS_INVALID {
+                continue;
+            }
+            if id == ID_ANY {
+                // Only the first one is the default entry, unused slots have the same id
+                table.default.get_or_insert(permissions);
+            } else {
+                table.entries.insert(id, permissions);
+            }
+        }
+        table
+    }
+
+    /// Effective permissions on `id`, from its own entry or the default one
+    pub fn get(&self, id: u32) -> PermissionFlags {
+        self.entries
+            .get(&id)
+            .copied()
+            .or(self.default)
+            .unwrap_or(PermissionFlags::empty())
+    }
+
+    /// Set the permissions on `id`, [`ID_ANY`] sets the default entry
+    pub fn set(&mut self, id: u32, permissions: PermissionFlags) {
+        if id == ID_ANY {
+            self.default = Some(permissions);
+        } else {
+            self.entries.insert(id, permissions);
+        }
+    }
+
+    /// Add `permissions` to the effective permissions on `id`
+    pub fn grant(&mut self, id: u32, permissions: PermissionFlags) {
+        self.set(id, self.get(id) | permissions);
+    }
+
+    /// Remove `permissions` from the effective permissions on `id`
+    pub fn revoke(&mut self, id: u32, permissions: PermissionFlags) {
+        self.set(id, self.get(id) - permissions);
+    }
+
+    /// Entries of specific globals, without the default entry
+    pub fn iter(&self) -> impl Iterator<Item = Permission> + '_ {
+        self.entries
+            .iter()
+            .map(|(id, permissions)| Permission::new(*id, *permissions))
+    }
+
+    /// All entries, the default one first, for [`methods::UpdatePermissions`]
+    pub fn to_permissions(&self) -> Vec<Permission> {
+        self.default
+            .map(|permissions| Permission::new(ID_ANY, permissions))
+            .into_iter()
+            .chain(self.iter())
+            .collect()
+    }
+}
+
 pub mod methods {
     use super::*;
 
@@ -60,6 +174,7 @@ pub mod methods {
 
     impl MethodSerializeSimple for Error {
         const OPCODE: u8 = 1;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, mut buf: impl Write + Seek) {
             pod::Builder::new(&mut buf).push_struct_with(|b| {
                 b.write_u32(self.id);
@@ -80,6 +195,7 @@ pub mod methods {
 
     impl MethodSerializeSimple for UpdateProperties {
         const OPCODE: u8 = 2;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, mut buf: impl Write + Seek) {
             pod::Builder::new(&mut buf).push_struct_with(|b| {
                 b.push_struct_with(|b| {
@@ -106,6 +222,7 @@ pub mod methods {
 
     impl MethodSerializeSimple for GetPermissions {
         const OPCODE: u8 = 3;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, mut buf: impl Write + Seek) {
             pod::Builder::new(&mut buf).push_struct_with(|b| {
                 b.write_u32(self.index);
@@ -116,12 +233,16 @@ pub mod methods {
 
     /// Update the permissions of the global objects using the provided array with permissions
     ///
+    /// The entries replace the existing ones of the same ids, an entry for [`ID_ANY`] replaces
+    /// the default.
+    ///
     /// This requires W and X permissions on the client.
     #[derive(Debug, Clone)]
     pub struct UpdatePermissions(pub Vec<Permission>);
 
     impl MethodSerializeSimple for UpdatePermissions {
         const OPCODE: u8 = 4;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, mut buf: impl Write + Seek) {
             pod::Builder::new(&mut buf).push_struct_with(|b| {
                 b.write_u32(self.0.len() as u32);
@@ -154,14 +275,13 @@ pub mod events {
 
         let mut list = Vec::with_capacity(len as usize);
         for _ in 0..len {
-            let id = pod.pop_field()?.as_i32()?;
+            // The default entry has the id `ID_ANY`, sent as -1
+            let id = pod.pop_field()?.as_i32()? as u32;
             let permissions = pod.pop_field()?.as_u32()?;
-            if let Ok(id) = u32::try_from(id) {
-                list.push(Permission {
-                    id,
-                    permissions: PermissionFlags::from_bits_retain(permissions),
-                });
-            }
+            list.push(Permission {
+                id,
+                permissions: PermissionFlags::from_bits_retain(permissions),
+            });
         }
 
         Ok(list)
@@ -183,7 +303,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -205,10 +325,11 @@ pub mod events {
 
     impl EventDeserialize for Permissions {
         const OPCODE: u8 = 1;
+        const SINCE: u32 = 3;
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -225,6 +346,11 @@ pub enum Event {
     Info(events::Info),
     /// Emitted as the reply of the GetPermissions method.
     Permissions(events::Permissions),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
 }
 
 impl HasInterface for Event {
@@ -0,0 +1,204 @@
+use super::*;
+
+pub mod methods {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct AddListener {}
+
+    impl MethodSerializeSimple for AddListener {
+        const OPCODE: u8 = 0;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            unreachable!()
+        }
+    }
+
+    bitflags::bitflags! {
+        #[derive(Debug, Clone, Copy)]
+        pub struct UpdateChangeMask: u32 {
+            const PARAMS = 1 << 0;
+            const INFO = 1 << 1;
+            /// Only valid for stream updates, removes the stream
+            const DESTROYED = 1 << 2;
+        }
+    }
+
+    /// Update the params and info of the exported endpoint
+    #[derive(Debug, Clone)]
+    pub struct Update {
+        /// A bitfield of changed items
+        pub change_mask: UpdateChangeMask,
+        /// The complete list of params, valid when change_mask has `PARAMS`
+        pub params: Vec<pod::serialize::OwnedPod>,
+        /// The updated info, valid when change_mask has `INFO`
+        pub info: Option<pw_endpoint::events::Info>,
+    }
+
+    impl MethodSerializeSimple for Update {
+        const OPCODE: u8 = 1;
+        fn serialize_simple(&self, mut buf: impl Write + Seek) {
+            pod::Builder::new(&mut buf).push_struct_with(|b| {
+                b.write_u32(self.change_mask.bits());
+                b.write_u32(self.params.len() as u32);
+                for param in self.params.iter() {
+                    b.write_pod(param);
+                }
+                if let Some(info) = self.info.as_ref() {
+                    info.serialize(b);
+                } else {
+                    b.write_none();
+                }
+            });
+        }
+    }
+
+    /// Add, update or remove a stream of the exported endpoint
+    ///
+    /// The stream is removed when `change_mask` has `DESTROYED`.
+    #[derive(Debug, Clone)]
+    pub struct StreamUpdate {
+        pub stream_id: u32,
+        /// A bitfield of changed items
+        pub change_mask: UpdateChangeMask,
+        /// The complete list of params, valid when change_mask has `PARAMS`
+        pub params: Vec<pod::serialize::OwnedPod>,
+        /// The updated info, valid when change_mask has `INFO`
+        pub info: Option<pw_endpoint_stream::events::Info>,
+    }
+
+    impl MethodSerializeSimple for StreamUpdate {
+        const OPCODE: u8 = 2;
+        fn serialize_simple(&self, mut buf: impl Write + Seek) {
+            pod::Builder::new(&mut buf).push_struct_with(|b| {
+                b.write_u32(self.stream_id);
+                b.write_u32(self.change_mask.bits());
+                b.write_u32(self.params.len() as u32);
+                for param in self.params.iter() {
+                    b.write_pod(param);
+                }
+                if let Some(info) = self.info.as_ref() {
+                    info.serialize(b);
+                } else {
+                    b.write_none();
+                }
+            });
+        }
+    }
+}
+
+pub mod events {
+    use super::*;
+
+    /// Tell the endpoint which session it belongs to
+    #[derive(Debug, Clone)]
+    pub struct SetSessionId {
+        pub id: u32,
+    }
+
+    impl EventDeserialize for SetSessionId {
+        const OPCODE: u8 = 0;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            let mut pod = pod.as_struct()?;
+            Ok(Self {
+                id: pod.pop_field()?.as_u32()?,
+            })
+        }
+    }
+
+    /// Set a parameter on the endpoint, forwarded from [`pw_endpoint::methods::SetParam`]
+    #[derive(Debug, Clone)]
+    pub struct SetParam {
+        pub id: SpaEnum<SpaParamType>,
+        pub flags: u32,
+        pub param: OwnedPod,
+    }
+
+    impl EventDeserialize for SetParam {
+        const OPCODE: u8 = 1;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            let mut pod = pod.as_struct()?;
+            Ok(Self {
+                id: SpaEnum::from_raw(pod.pop_field()?.as_id()?),
+                flags: pod.pop_field()?.as_u32()?,
+                param: pod.pop_field()?.to_owned(),
+            })
+        }
+    }
+
+    /// Set a parameter on a stream of the endpoint
+    #[derive(Debug, Clone)]
+    pub struct StreamSetParam {
+        pub stream_id: u32,
+        pub id: SpaEnum<SpaParamType>,
+        pub flags: u32,
+        pub param: OwnedPod,
+    }
+
+    impl EventDeserialize for StreamSetParam {
+        const OPCODE: u8 = 2;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            let mut pod = pod.as_struct()?;
+            Ok(Self {
+                stream_id: pod.pop_field()?.as_u32()?,
+                id: SpaEnum::from_raw(pod.pop_field()?.as_id()?),
+                flags: pod.pop_field()?.as_u32()?,
+                param: pod.pop_field()?.to_owned(),
+            })
+        }
+    }
+
+    /// Create a link, forwarded from [`pw_endpoint::methods::CreateLink`]
+    #[derive(Debug, Clone)]
+    pub struct CreateLink {
+        pub properties: PwDictionary,
+    }
+
+    impl EventDeserialize for CreateLink {
+        const OPCODE: u8 = 3;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            Ok(Self {
+                properties: parse_dict(&mut pod.as_struct()?)?,
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone, pod_derive::EventDeserialize)]
+pub enum Event {
+    /// Tell the endpoint which session it belongs to
+    SetSessionId(events::SetSessionId),
+
+    /// Set a parameter on the endpoint
+    SetParam(events::SetParam),
+
+    /// Set a parameter on a stream of the endpoint
+    StreamSetParam(events::StreamSetParam),
+
+    /// Create a link
+    CreateLink(events::CreateLink),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
+}
+
+impl HasInterface for Event {
+    const INTERFACE: &'static str = "ClientEndpoint";
+}
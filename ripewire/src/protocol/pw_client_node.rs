@@ -23,6 +23,7 @@ pub mod methods {
 
     impl MethodSerializeSimple for GetNode {
         const OPCODE: u8 = 1;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, mut buf: impl Write + Seek) {
             pod::Builder::new(&mut buf).push_struct_with(|b| {
                 b.write_u32(self.version);
@@ -383,13 +384,13 @@ pub mod methods {
     }
 
     #[derive(Debug, Clone)]
-    pub struct PortBufferDataPlane {
+    pub struct PortBufferDataPlane<'a> {
         /// The plane memory type:
         /// - SPA_DATA_MemId to reference a memfd from Core:AddMem
         /// - SPA_DATA_MemPtr to reference this buffer memid
         pub type_: SpaEnum<SpaDataType>,
         /// The plane memfd
-        pub memfd: RawFd,
+        pub memfd: BorrowedFd<'a>,
         /// Extra flags for the data
         pub flags: u32,
         /// The start offset of where the buffer memory starts
@@ -402,20 +403,21 @@ pub mod methods {
     ///
     /// It is usually called right after the UseBuffers event to let the server know about the the newly allocated buffer memory.
     #[derive(Debug, Clone)]
-    pub struct PortBuffers {
+    pub struct PortBuffers<'a> {
         /// The port direction
         pub direction: SpaEnum<SpaDirection>,
         /// The port id
         pub port_id: u32,
         /// The mix id of the port
         pub mix_id: u32,
-        pub buffers: Vec<Vec<PortBufferDataPlane>>,
+        pub buffers: Vec<Vec<PortBufferDataPlane<'a>>>,
     }
 
-    impl MethodSerialize for PortBuffers {
+    impl MethodSerialize for PortBuffers<'_> {
         const OPCODE: u8 = 6;
+        const SINCE: u32 = 3;
 
-        fn serialize(&self, mut buf: impl Write + Seek, fds: &mut Vec<RawFd>) {
+        fn serialize<'a>(&'a self, mut buf: impl Write + Seek, fds: &mut Vec<BorrowedFd<'a>>) {
             pod::Builder::new(&mut buf).push_struct_with(|b| {
                 b.write_u32(self.direction.as_raw());
                 b.write_u32(self.port_id);
@@ -440,8 +442,6 @@ pub mod methods {
 }
 
 pub mod events {
-    use std::os::fd::RawFd;
-
     use super::*;
 
     /// The server will allocate the activation record and eventfd for the node and transfer this to the client with the Transport event.
@@ -451,7 +451,7 @@ pub mod events {
     /// The writefd is meant to wake up the server after the driver completes so that the profiler can collect the information.
     /// The profiler is active when the pw_node_activation::flags fields has PW_NODE_ACTIVATION_FLAG_PROFILER set.
     /// When the profiler is disabled (or when the node is not driving), this eventfd should not be signaled.
-    #[derive(Debug, Clone)]
+    #[derive(Debug)]
     pub struct Transport {
         /// The eventfd to start processing
         pub readfd: Fd,
@@ -468,23 +468,14 @@ pub mod events {
     impl EventDeserialize for Transport {
         const OPCODE: u8 = 0;
 
-        fn deserialize(pod: &mut PodDeserializer, fds: &[RawFd]) -> pod::deserialize::Result<Self> {
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
-                readfd: {
-                    let id = pod.pop_field()?.as_fd()?;
-                    Fd {
-                        id,
-                        fd: fds.get(id as usize).copied(),
-                    }
-                },
-                writefd: {
-                    let id = pod.pop_field()?.as_fd()?;
-                    Fd {
-                        id,
-                        fd: fds.get(id as usize).copied(),
-                    }
-                },
+                readfd: parse_fd(&mut pod, fds)?,
+                writefd: parse_fd(&mut pod, fds)?,
                 memid: pod.pop_field()?.as_u32()?,
                 offset: pod.pop_field()?.as_u32()?,
                 size: pod.pop_field()?.as_u32()?,
@@ -508,7 +499,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut PodDeserializer,
-            _fds: &[RawFd],
+            _fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -537,7 +528,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut PodDeserializer,
-            _fds: &[RawFd],
+            _fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -561,7 +552,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut PodDeserializer,
-            _fds: &[RawFd],
+            _fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -577,12 +568,20 @@ pub mod events {
         pub command: OwnedPod,
     }
 
+    impl Command {
+        pub fn node_command(
+            &self,
+        ) -> pod::deserialize::Result<SpaEnum<crate::protocol::pw_node::NodeCommand>> {
+            crate::protocol::pw_node::NodeCommand::from_pod(&self.command.as_deserializer())
+        }
+    }
+
     impl EventDeserialize for Command {
         const OPCODE: u8 = 4;
 
         fn deserialize(
             pod: &mut PodDeserializer,
-            _fds: &[RawFd],
+            _fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -607,7 +606,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut PodDeserializer,
-            _fds: &[RawFd],
+            _fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -632,7 +631,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut PodDeserializer,
-            _fds: &[RawFd],
+            _fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -662,7 +661,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut PodDeserializer,
-            _fds: &[RawFd],
+            _fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -770,7 +769,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut PodDeserializer,
-            _fds: &[RawFd],
+            _fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -814,7 +813,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut PodDeserializer,
-            _fds: &[RawFd],
+            _fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -831,7 +830,7 @@ pub mod events {
 
     /// Notify the client of the activation record of a peer node.
     /// This activation record should be triggered when this node finishes processing.
-    #[derive(Debug, Clone)]
+    #[derive(Debug)]
     pub struct SetActivation {
         /// The node_id of the peer node
         pub node_id: u32,
@@ -847,18 +846,16 @@ pub mod events {
 
     impl EventDeserialize for SetActivation {
         const OPCODE: u8 = 10;
+        const SINCE: u32 = 3;
 
-        fn deserialize(pod: &mut PodDeserializer, fds: &[RawFd]) -> pod::deserialize::Result<Self> {
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
                 node_id: pod.pop_field()?.as_u32()?,
-                signalfd: {
-                    let id = pod.pop_field()?.as_fd()?;
-                    Fd {
-                        id,
-                        fd: usize::try_from(id).ok().and_then(|id| fds.get(id)).copied(),
-                    }
-                },
+                signalfd: parse_fd(&mut pod, fds)?,
                 memid: pod.pop_field()?.as_u32()?,
                 offset: pod.pop_field()?.as_u32()?,
                 size: pod.pop_field()?.as_u32()?,
@@ -884,10 +881,11 @@ pub mod events {
 
     impl EventDeserialize for PortSetMixInfo {
         const OPCODE: u8 = 11;
+        const SINCE: u32 = 4;
 
         fn deserialize(
             pod: &mut PodDeserializer,
-            _fds: &[RawFd],
+            _fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -901,7 +899,7 @@ pub mod events {
     }
 }
 
-#[derive(Debug, Clone, pod_derive::EventDeserialize)]
+#[derive(Debug, pod_derive::EventDeserialize)]
 pub enum Event {
     Transport(events::Transport),
     SetParam(events::SetParam),
@@ -915,6 +913,11 @@ pub enum Event {
     PortSetIo(events::PortSetIo),
     SetActivation(events::SetActivation),
     PortSetMixInfo(events::PortSetMixInfo),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
 }
 
 impl HasInterface for Event {
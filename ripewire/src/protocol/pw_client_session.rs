@@ -0,0 +1,186 @@
+use super::*;
+use libspa_consts::PwEndpointLinkState;
+
+pub mod methods {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct AddListener {}
+
+    impl MethodSerializeSimple for AddListener {
+        const OPCODE: u8 = 0;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            unreachable!()
+        }
+    }
+
+    bitflags::bitflags! {
+        #[derive(Debug, Clone, Copy)]
+        pub struct UpdateChangeMask: u32 {
+            const PARAMS = 1 << 0;
+            const INFO = 1 << 1;
+            /// Only valid for link updates, removes the link
+            const DESTROYED = 1 << 2;
+        }
+    }
+
+    /// Update the params and info of the exported session
+    #[derive(Debug, Clone)]
+    pub struct Update {
+        /// A bitfield of changed items
+        pub change_mask: UpdateChangeMask,
+        /// The complete list of params, valid when change_mask has `PARAMS`
+        pub params: Vec<pod::serialize::OwnedPod>,
+        /// The updated info, valid when change_mask has `INFO`
+        pub info: Option<pw_session::events::Info>,
+    }
+
+    impl MethodSerializeSimple for Update {
+        const OPCODE: u8 = 1;
+        fn serialize_simple(&self, mut buf: impl Write + Seek) {
+            pod::Builder::new(&mut buf).push_struct_with(|b| {
+                b.write_u32(self.change_mask.bits());
+                b.write_u32(self.params.len() as u32);
+                for param in self.params.iter() {
+                    b.write_pod(param);
+                }
+                if let Some(info) = self.info.as_ref() {
+                    info.serialize(b);
+                } else {
+                    b.write_none();
+                }
+            });
+        }
+    }
+
+    /// Add, update or remove a link of the exported session
+    ///
+    /// The link is removed when `change_mask` has `DESTROYED`.
+    #[derive(Debug, Clone)]
+    pub struct LinkUpdate {
+        pub link_id: u32,
+        /// A bitfield of changed items
+        pub change_mask: UpdateChangeMask,
+        /// The complete list of params, valid when change_mask has `PARAMS`
+        pub params: Vec<pod::serialize::OwnedPod>,
+        /// The updated info, valid when change_mask has `INFO`
+        pub info: Option<pw_endpoint_link::events::Info>,
+    }
+
+    impl MethodSerializeSimple for LinkUpdate {
+        const OPCODE: u8 = 2;
+        fn serialize_simple(&self, mut buf: impl Write + Seek) {
+            pod::Builder::new(&mut buf).push_struct_with(|b| {
+                b.write_u32(self.link_id);
+                b.write_u32(self.change_mask.bits());
+                b.write_u32(self.params.len() as u32);
+                for param in self.params.iter() {
+                    b.write_pod(param);
+                }
+                if let Some(info) = self.info.as_ref() {
+                    info.serialize(b);
+                } else {
+                    b.write_none();
+                }
+            });
+        }
+    }
+}
+
+pub mod events {
+    use super::*;
+
+    /// Set a parameter on the session, forwarded from [`pw_session::methods::SetParam`]
+    #[derive(Debug, Clone)]
+    pub struct SetParam {
+        pub id: SpaEnum<SpaParamType>,
+        pub flags: u32,
+        pub param: OwnedPod,
+    }
+
+    impl EventDeserialize for SetParam {
+        const OPCODE: u8 = 0;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            let mut pod = pod.as_struct()?;
+            Ok(Self {
+                id: SpaEnum::from_raw(pod.pop_field()?.as_id()?),
+                flags: pod.pop_field()?.as_u32()?,
+                param: pod.pop_field()?.to_owned(),
+            })
+        }
+    }
+
+    /// Set a parameter on a link of the session
+    #[derive(Debug, Clone)]
+    pub struct LinkSetParam {
+        pub link_id: u32,
+        pub id: SpaEnum<SpaParamType>,
+        pub flags: u32,
+        pub param: OwnedPod,
+    }
+
+    impl EventDeserialize for LinkSetParam {
+        const OPCODE: u8 = 1;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            let mut pod = pod.as_struct()?;
+            Ok(Self {
+                link_id: pod.pop_field()?.as_u32()?,
+                id: SpaEnum::from_raw(pod.pop_field()?.as_id()?),
+                flags: pod.pop_field()?.as_u32()?,
+                param: pod.pop_field()?.to_owned(),
+            })
+        }
+    }
+
+    /// Move a link of the session to `state`,
+    /// forwarded from [`pw_endpoint_link::methods::RequestState`]
+    #[derive(Debug, Clone)]
+    pub struct LinkRequestState {
+        pub link_id: u32,
+        pub state: SpaEnum<PwEndpointLinkState, i32>,
+    }
+
+    impl EventDeserialize for LinkRequestState {
+        const OPCODE: u8 = 2;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            let mut pod = pod.as_struct()?;
+            Ok(Self {
+                link_id: pod.pop_field()?.as_u32()?,
+                state: SpaEnum::from_i32(pod.pop_field()?.as_i32()?),
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone, pod_derive::EventDeserialize)]
+pub enum Event {
+    /// Set a parameter on the session
+    SetParam(events::SetParam),
+
+    /// Set a parameter on a link of the session
+    LinkSetParam(events::LinkSetParam),
+
+    /// Move a link of the session to a new state
+    LinkRequestState(events::LinkRequestState),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
+}
+
+impl HasInterface for Event {
+    const INTERFACE: &'static str = "ClientSession";
+}
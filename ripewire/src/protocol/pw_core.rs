@@ -1,5 +1,3 @@
-use std::os::fd::RawFd;
-
 use super::*;
 
 pub const OBJECT_ID: u32 = 0;
@@ -72,6 +70,7 @@ pub mod methods {
 
     impl MethodSerializeSimple for Pong {
         const OPCODE: u8 = 3;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, mut buff: impl Write + Seek) {
             pod::Builder::new(&mut buff).push_struct_with(|b| {
                 b.write_u32(self.id);
@@ -95,6 +94,7 @@ pub mod methods {
 
     impl MethodSerializeSimple for Error {
         const OPCODE: u8 = 4;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, mut buff: impl Write + Seek) {
             pod::Builder::new(&mut buff).push_struct_with(|b| {
                 b.write_u32(self.id);
@@ -232,7 +232,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -261,7 +261,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -289,10 +289,11 @@ pub mod events {
 
     impl EventDeserialize for Ping {
         const OPCODE: u8 = 2;
+        const SINCE: u32 = 3;
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -322,7 +323,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -349,7 +350,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -369,10 +370,11 @@ pub mod events {
 
     impl EventDeserialize for BoundId {
         const OPCODE: u8 = 5;
+        const SINCE: u32 = 3;
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -388,7 +390,7 @@ pub mod events {
     /// memory `type`.
     ///
     /// Further references to this fd will be made with the per memory\nunique identifier `id`.
-    #[derive(Debug, Clone)]
+    #[derive(Debug)]
     pub struct AddMem {
         pub id: u32,
         pub ty: SpaEnum<SpaDataType>,
@@ -398,22 +400,17 @@ pub mod events {
 
     impl EventDeserialize for AddMem {
         const OPCODE: u8 = 6;
+        const SINCE: u32 = 3;
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
                 id: pod.pop_field()?.as_u32()?,
                 ty: SpaEnum::from_raw(pod.pop_field()?.as_id()?),
-                fd: {
-                    let id = pod.pop_field()?.as_fd()?;
-                    Fd {
-                        id,
-                        fd: fds.get(id as usize).copied(),
-                    }
-                },
+                fd: parse_fd(&mut pod, fds)?,
                 flags: MemblockFlags::from_bits_retain(pod.pop_field()?.as_u32()?),
             })
         }
@@ -427,10 +424,11 @@ pub mod events {
 
     impl EventDeserialize for RemoveMem {
         const OPCODE: u8 = 7;
+        const SINCE: u32 = 3;
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -452,10 +450,11 @@ pub mod events {
 
     impl EventDeserialize for BoundProps {
         const OPCODE: u8 = 8;
+        const SINCE: u32 = 4;
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -467,7 +466,7 @@ pub mod events {
     }
 }
 
-#[derive(Debug, Clone, pod_derive::EventDeserialize)]
+#[derive(Debug, pod_derive::EventDeserialize)]
 pub enum Event {
     /// This event is emitted when first bound to the core or when the hello method is called.
     Info(events::Info),
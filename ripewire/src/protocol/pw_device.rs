@@ -29,6 +29,7 @@ pub mod methods {
 
     impl MethodSerializeSimple for SubscribeParams {
         const OPCODE: u8 = 1;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, buf: impl Write + Seek) {
             pod::Builder::new(buf).push_struct_with(|b| {
                 b.write_array_with(|b| {
@@ -125,7 +126,10 @@ pub mod events {
     impl EventDeserialize for Info {
         const OPCODE: u8 = 0;
 
-        fn deserialize(pod: &mut PodDeserializer, fds: &[RawFd]) -> pod::deserialize::Result<Self> {
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
                 id: pod.pop_field()?.as_u32()?,
@@ -158,7 +162,10 @@ pub mod events {
     impl EventDeserialize for Param {
         const OPCODE: u8 = 1;
 
-        fn deserialize(pod: &mut PodDeserializer, fds: &[RawFd]) -> pod::deserialize::Result<Self> {
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
                 seq: pod.pop_field()?.as_i32()?,
@@ -180,6 +187,11 @@ pub enum Event {
     ///
     /// Event emitted as a result of the enum_params method.
     Param(events::Param),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
 }
 
 impl HasInterface for Event {
@@ -0,0 +1,141 @@
+use super::*;
+use libspa_consts::SpaDirection;
+
+pub mod methods {
+    use super::*;
+
+    pub use crate::protocol::pw_session::methods::{EnumParams, SetParam, SubscribeParams};
+
+    #[derive(Debug, Clone)]
+    pub struct AddListener {}
+
+    impl MethodSerializeSimple for AddListener {
+        const OPCODE: u8 = 0;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            unreachable!()
+        }
+    }
+
+    /// Ask the session manager to create a link from this endpoint
+    ///
+    /// properties - describe the link, eg. `endpoint-link.input.endpoint`,
+    /// `endpoint-link.input.stream` and `endpoint-link.output.stream`
+    #[derive(Debug, Clone)]
+    pub struct CreateLink {
+        pub properties: PwDictionary,
+    }
+
+    impl MethodSerializeSimple for CreateLink {
+        const OPCODE: u8 = 4;
+        fn serialize_simple(&self, mut buf: impl Write + Seek) {
+            write_dict(&mut pod::Builder::new(&mut buf), &self.properties);
+        }
+    }
+}
+
+pub use events::{ChangeMask, EndpointFlags};
+pub mod events {
+    use super::*;
+
+    pub use crate::protocol::pw_session::events::Param;
+
+    bitflags::bitflags! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct ChangeMask: u32 {
+            const STREAMS = 1 << 0;
+            const SESSION = 1 << 1;
+            const PROPS = 1 << 2;
+            const PARAMS = 1 << 3;
+        }
+    }
+
+    bitflags::bitflags! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct EndpointFlags: u32 {
+            /// The endpoint exposes a session, see `session_id`
+            const PROVIDES_SESSION = 1 << 0;
+        }
+    }
+
+    /// Notify endpoint info
+    #[derive(Debug, Clone)]
+    pub struct Info {
+        /// Version of the info struct, currently 0
+        pub version: u32,
+        pub id: u32,
+        pub name: String,
+        pub media_class: String,
+        pub direction: SpaEnum<SpaDirection>,
+        pub flags: EndpointFlags,
+        pub change_mask: ChangeMask,
+        pub n_streams: u32,
+        pub session_id: u32,
+        pub props: PwDictionary,
+        pub params: Vec<ParamInfo>,
+    }
+
+    impl Info {
+        pub(crate) fn parse(pod: &mut PodStructDeserializer) -> pod::deserialize::Result<Self> {
+            Ok(Self {
+                version: pod.pop_field()?.as_u32()?,
+                id: pod.pop_field()?.as_u32()?,
+                name: pod.pop_field()?.as_str()?.to_string(),
+                media_class: pod.pop_field()?.as_str()?.to_string(),
+                direction: SpaEnum::from_raw(pod.pop_field()?.as_u32()?),
+                flags: EndpointFlags::from_bits_retain(pod.pop_field()?.as_u32()?),
+                change_mask: ChangeMask::from_bits_retain(pod.pop_field()?.as_u32()?),
+                n_streams: pod.pop_field()?.as_u32()?,
+                session_id: pod.pop_field()?.as_u32()?,
+                props: parse_dict(&mut pod.pop_field()?.as_struct()?)?,
+                params: parse_params(&mut pod.pop_field()?.as_struct()?)?,
+            })
+        }
+
+        pub(crate) fn serialize(&self, b: &mut pod::Builder<impl Write + Seek>) {
+            b.push_struct_with(|b| {
+                b.write_u32(self.version);
+                b.write_u32(self.id);
+                b.write_str(&self.name);
+                b.write_str(&self.media_class);
+                b.write_u32(self.direction.as_raw());
+                b.write_u32(self.flags.bits());
+                b.write_u32(self.change_mask.bits());
+                b.write_u32(self.n_streams);
+                b.write_u32(self.session_id);
+                write_dict(b, &self.props);
+                write_params(b, &self.params);
+            });
+        }
+    }
+
+    impl EventDeserialize for Info {
+        const OPCODE: u8 = 0;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            Self::parse(&mut pod.as_struct()?)
+        }
+    }
+}
+
+#[derive(Debug, Clone, pod_derive::EventDeserialize)]
+pub enum Event {
+    /// Notify endpoint info
+    Info(events::Info),
+
+    /// Notify an endpoint param
+    ///
+    /// Event emitted as a result of the enum_params method.
+    Param(events::Param),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
+}
+
+impl HasInterface for Event {
+    const INTERFACE: &'static str = "Endpoint";
+}
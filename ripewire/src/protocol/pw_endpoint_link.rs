@@ -0,0 +1,137 @@
+use super::*;
+use libspa_consts::PwEndpointLinkState;
+
+pub mod methods {
+    use super::*;
+
+    pub use crate::protocol::pw_session::methods::{EnumParams, SetParam, SubscribeParams};
+
+    #[derive(Debug, Clone)]
+    pub struct AddListener {}
+
+    impl MethodSerializeSimple for AddListener {
+        const OPCODE: u8 = 0;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            unreachable!()
+        }
+    }
+
+    /// Ask the session manager to move the link to `state`
+    #[derive(Debug, Clone)]
+    pub struct RequestState {
+        pub state: PwEndpointLinkState,
+    }
+
+    impl MethodSerializeSimple for RequestState {
+        const OPCODE: u8 = 4;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            pod::Builder::new(buf).push_struct_with(|b| {
+                b.write_i32(self.state as i32);
+            });
+        }
+    }
+}
+
+pub use events::ChangeMask;
+pub mod events {
+    use super::*;
+
+    pub use crate::protocol::pw_session::events::Param;
+
+    bitflags::bitflags! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct ChangeMask: u32 {
+            const STATE = 1 << 0;
+            const PROPS = 1 << 1;
+            const PARAMS = 1 << 2;
+        }
+    }
+
+    /// Notify endpoint link info
+    #[derive(Debug, Clone)]
+    pub struct Info {
+        /// Version of the info struct, currently 0
+        pub version: u32,
+        pub id: u32,
+        pub session_id: u32,
+        pub output_endpoint_id: u32,
+        pub output_stream_id: u32,
+        pub input_endpoint_id: u32,
+        pub input_stream_id: u32,
+        pub change_mask: ChangeMask,
+        pub state: SpaEnum<PwEndpointLinkState, i32>,
+        pub error: Option<String>,
+        pub props: PwDictionary,
+        pub params: Vec<ParamInfo>,
+    }
+
+    impl Info {
+        pub(crate) fn parse(pod: &mut PodStructDeserializer) -> pod::deserialize::Result<Self> {
+            Ok(Self {
+                version: pod.pop_field()?.as_u32()?,
+                id: pod.pop_field()?.as_u32()?,
+                session_id: pod.pop_field()?.as_u32()?,
+                output_endpoint_id: pod.pop_field()?.as_u32()?,
+                output_stream_id: pod.pop_field()?.as_u32()?,
+                input_endpoint_id: pod.pop_field()?.as_u32()?,
+                input_stream_id: pod.pop_field()?.as_u32()?,
+                change_mask: ChangeMask::from_bits_retain(pod.pop_field()?.as_u32()?),
+                state: SpaEnum::from_i32(pod.pop_field()?.as_i32()?),
+                error: pod.pop_field()?.as_str_or_none()?.map(ToString::to_string),
+                props: parse_dict(&mut pod.pop_field()?.as_struct()?)?,
+                params: parse_params(&mut pod.pop_field()?.as_struct()?)?,
+            })
+        }
+
+        pub(crate) fn serialize(&self, b: &mut pod::Builder<impl Write + Seek>) {
+            b.push_struct_with(|b| {
+                b.write_u32(self.version);
+                b.write_u32(self.id);
+                b.write_u32(self.session_id);
+                b.write_u32(self.output_endpoint_id);
+                b.write_u32(self.output_stream_id);
+                b.write_u32(self.input_endpoint_id);
+                b.write_u32(self.input_stream_id);
+                b.write_u32(self.change_mask.bits());
+                b.write_i32(self.state.as_raw());
+                match &self.error {
+                    Some(error) => b.write_str(error),
+                    None => b.write_none(),
+                };
+                write_dict(b, &self.props);
+                write_params(b, &self.params);
+            });
+        }
+    }
+
+    impl EventDeserialize for Info {
+        const OPCODE: u8 = 0;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            Self::parse(&mut pod.as_struct()?)
+        }
+    }
+}
+
+#[derive(Debug, Clone, pod_derive::EventDeserialize)]
+pub enum Event {
+    /// Notify endpoint link info
+    Info(events::Info),
+
+    /// Notify an endpoint link param
+    ///
+    /// Event emitted as a result of the enum_params method.
+    Param(events::Param),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
+}
+
+impl HasInterface for Event {
+    const INTERFACE: &'static str = "EndpointLink";
+}
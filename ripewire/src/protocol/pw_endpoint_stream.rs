@@ -0,0 +1,107 @@
+use super::*;
+
+pub mod methods {
+    use super::*;
+
+    pub use crate::protocol::pw_session::methods::{EnumParams, SetParam, SubscribeParams};
+
+    #[derive(Debug, Clone)]
+    pub struct AddListener {}
+
+    impl MethodSerializeSimple for AddListener {
+        const OPCODE: u8 = 0;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            unreachable!()
+        }
+    }
+}
+
+pub use events::ChangeMask;
+pub mod events {
+    use super::*;
+
+    pub use crate::protocol::pw_session::events::Param;
+
+    bitflags::bitflags! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct ChangeMask: u32 {
+            const LINK_PARAMS = 1 << 0;
+            const PROPS = 1 << 1;
+            const PARAMS = 1 << 2;
+        }
+    }
+
+    /// Notify endpoint stream info
+    #[derive(Debug, Clone)]
+    pub struct Info {
+        /// Version of the info struct, currently 0
+        pub version: u32,
+        pub id: u32,
+        pub endpoint_id: u32,
+        pub name: String,
+        pub change_mask: ChangeMask,
+        /// Pod bytes, describes what the stream can be linked with
+        pub link_params: OwnedPod,
+        pub props: PwDictionary,
+        pub params: Vec<ParamInfo>,
+    }
+
+    impl Info {
+        pub(crate) fn parse(pod: &mut PodStructDeserializer) -> pod::deserialize::Result<Self> {
+            Ok(Self {
+                version: pod.pop_field()?.as_u32()?,
+                id: pod.pop_field()?.as_u32()?,
+                endpoint_id: pod.pop_field()?.as_u32()?,
+                name: pod.pop_field()?.as_str()?.to_string(),
+                change_mask: ChangeMask::from_bits_retain(pod.pop_field()?.as_u32()?),
+                link_params: pod.pop_field()?.to_owned(),
+                props: parse_dict(&mut pod.pop_field()?.as_struct()?)?,
+                params: parse_params(&mut pod.pop_field()?.as_struct()?)?,
+            })
+        }
+
+        pub(crate) fn serialize(&self, b: &mut pod::Builder<impl Write + Seek>) {
+            b.push_struct_with(|b| {
+                b.write_u32(self.version);
+                b.write_u32(self.id);
+                b.write_u32(self.endpoint_id);
+                b.write_str(&self.name);
+                b.write_u32(self.change_mask.bits());
+                b.write_pod(&self.link_params.to_serialize());
+                write_dict(b, &self.props);
+                write_params(b, &self.params);
+            });
+        }
+    }
+
+    impl EventDeserialize for Info {
+        const OPCODE: u8 = 0;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            Self::parse(&mut pod.as_struct()?)
+        }
+    }
+}
+
+#[derive(Debug, Clone, pod_derive::EventDeserialize)]
+pub enum Event {
+    /// Notify endpoint stream info
+    Info(events::Info),
+
+    /// Notify an endpoint stream param
+    ///
+    /// Event emitted as a result of the enum_params method.
+    Param(events::Param),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
+}
+
+impl HasInterface for Event {
+    const INTERFACE: &'static str = "EndpointStream";
+}
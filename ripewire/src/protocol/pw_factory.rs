@@ -32,7 +32,9 @@ pub mod events {
     pub struct Info {
         pub id: u32,
         pub name: String,
-        pub interface: String,
+        /// Type of the objects created by the factory
+        pub interface: ObjectType,
+        /// Version of the objects created by the factory
         pub version: u32,
         pub change_mask: ChangeMask,
         pub props: PwDictionary,
@@ -41,12 +43,17 @@ pub mod events {
     impl EventDeserialize for Info {
         const OPCODE: u8 = 0;
 
-        fn deserialize(pod: &mut PodDeserializer, fds: &[RawFd]) -> pod::deserialize::Result<Self> {
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
                 id: pod.pop_field()?.as_u32()?,
                 name: pod.pop_field()?.as_str()?.to_string(),
-                interface: pod.pop_field()?.as_str()?.to_string(),
+                interface: ObjectType::from_interface_name(&String::from_utf8_lossy(
+                    pod.pop_field()?.as_str()?,
+                )),
                 version: pod.pop_field()?.as_u32()?,
                 change_mask: ChangeMask::from_bits_retain(pod.pop_field()?.as_u64()?),
                 props: parse_dict(&mut pod.pop_field()?.as_struct()?)?,
@@ -59,6 +66,11 @@ pub mod events {
 pub enum Event {
     /// Notify factory info
     Info(events::Info),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
 }
 
 impl HasInterface for Event {
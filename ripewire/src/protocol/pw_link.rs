@@ -48,7 +48,10 @@ pub mod events {
     impl EventDeserialize for Info {
         const OPCODE: u8 = 0;
 
-        fn deserialize(pod: &mut PodDeserializer, fds: &[RawFd]) -> pod::deserialize::Result<Self> {
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
                 id: pod.pop_field()?.as_u32()?,
@@ -70,6 +73,11 @@ pub mod events {
 pub enum Event {
     /// Notify link info
     Info(events::Info),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
 }
 
 impl HasInterface for Event {
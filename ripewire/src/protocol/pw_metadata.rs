@@ -0,0 +1,124 @@
+use super::*;
+use crate::spa_json::{self, SpaJson};
+
+/// Type of values that hold SPA-JSON, see [`crate::spa_json`]
+pub const TYPE_JSON: &str = "Spa:String:JSON";
+
+pub mod methods {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct AddListener {}
+
+    impl MethodSerializeSimple for AddListener {
+        const OPCODE: u8 = 0;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            unreachable!()
+        }
+    }
+
+    /// Set a metadata property
+    ///
+    /// - subject: the id of the object the property is about
+    /// - key: the key, `None` removes all properties of the subject
+    /// - ty: the type of the value, eg. [`TYPE_JSON`]
+    /// - value: the value, `None` removes the property
+    #[derive(Debug, Clone)]
+    pub struct SetProperty {
+        pub subject: u32,
+        pub key: Option<String>,
+        pub ty: Option<String>,
+        pub value: Option<String>,
+    }
+
+    impl MethodSerializeSimple for SetProperty {
+        const OPCODE: u8 = 1;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            pod::Builder::new(buf).push_struct_with(|b| {
+                b.write_u32(self.subject);
+                for v in [&self.key, &self.ty, &self.value] {
+                    match v {
+                        Some(v) => b.write_str(v),
+                        None => b.write_none(),
+                    };
+                }
+            });
+        }
+    }
+
+    /// Remove all metadata properties
+    #[derive(Debug, Clone)]
+    pub struct Clear {}
+
+    impl MethodSerializeSimple for Clear {
+        const OPCODE: u8 = 2;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            pod::Builder::new(buf).push_struct_with(|_b| {});
+        }
+    }
+}
+
+pub mod events {
+    use super::*;
+
+    /// Notify a property change
+    ///
+    /// Emitted for all existing properties when the metadata is bound, and for every
+    /// change after that.
+    ///
+    /// - subject: the id of the object the property is about
+    /// - key: the key, `None` when all properties of the subject were removed
+    /// - ty: the type of the value
+    /// - value: the new value, `None` when the property was removed
+    #[derive(Debug, Clone)]
+    pub struct Property {
+        pub subject: u32,
+        pub key: Option<String>,
+        pub ty: Option<String>,
+        pub value: Option<String>,
+    }
+
+    impl Property {
+        /// Parse the value as SPA-JSON
+        ///
+        /// ```ignore
+        /// // default.audio.sink = { "name": "alsa_output.pci-0000_00_1f.3.analog-stereo" }
+        /// let sink = property.json()?.ok()?.get("name")?.as_str()?;
+        /// ```
+        pub fn json(&self) -> Option<Result<SpaJson, spa_json::ParseError>> {
+            self.value.as_deref().map(SpaJson::parse)
+        }
+    }
+
+    impl EventDeserialize for Property {
+        const OPCODE: u8 = 0;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            let mut pod = pod.as_struct()?;
+            Ok(Self {
+                subject: pod.pop_field()?.as_u32()?,
+                key: pod.pop_field()?.as_str_or_none()?.map(ToString::to_string),
+                ty: pod.pop_field()?.as_str_or_none()?.map(ToString::to_string),
+                value: pod.pop_field()?.as_str_or_none()?.map(ToString::to_string),
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone, pod_derive::EventDeserialize)]
+pub enum Event {
+    /// Notify a property change
+    Property(events::Property),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
+}
+
+impl HasInterface for Event {
+    const INTERFACE: &'static str = "Metadata";
+}
@@ -1,4 +1,90 @@
 use super::*;
+use libspa_consts::{SpaNodeCommand, SpaType};
+
+/// Command for a node, see [`PwNode::send_command`]
+///
+/// [`PwNode::send_command`]: crate::proxy::PwNode::send_command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeCommand {
+    /// Remove all configured formats and close any devices
+    Suspend,
+    /// Stop emitting scheduling events
+    Pause,
+    /// Start emitting scheduling events
+    Start,
+    Enable,
+    Disable,
+    Flush,
+    Drain,
+    Marker,
+    /// Begin a set of parameter enumerations or configurations that require the device
+    /// to remain opened, like querying formats and then setting one
+    ParamBegin,
+    /// End a set of parameter enumerations or configurations
+    ParamEnd,
+    /// Sent to a driver when some other node emitted the RequestProcess event
+    RequestProcess,
+}
+
+impl NodeCommand {
+    pub fn id(self) -> SpaNodeCommand {
+        match self {
+            Self::Suspend => SpaNodeCommand::Suspend,
+            Self::Pause => SpaNodeCommand::Pause,
+            Self::Start => SpaNodeCommand::Start,
+            Self::Enable => SpaNodeCommand::Enable,
+            Self::Disable => SpaNodeCommand::Disable,
+            Self::Flush => SpaNodeCommand::Flush,
+            Self::Drain => SpaNodeCommand::Drain,
+            Self::Marker => SpaNodeCommand::Marker,
+            Self::ParamBegin => SpaNodeCommand::ParamBegin,
+            Self::ParamEnd => SpaNodeCommand::ParamEnd,
+            Self::RequestProcess => SpaNodeCommand::RequestProcess,
+        }
+    }
+
+    /// `None` for user defined commands
+    pub fn from_id(id: SpaNodeCommand) -> Option<Self> {
+        Some(match id {
+            SpaNodeCommand::Suspend => Self::Suspend,
+            SpaNodeCommand::Pause => Self::Pause,
+            SpaNodeCommand::Start => Self::Start,
+            SpaNodeCommand::Enable => Self::Enable,
+            SpaNodeCommand::Disable => Self::Disable,
+            SpaNodeCommand::Flush => Self::Flush,
+            SpaNodeCommand::Drain => Self::Drain,
+            SpaNodeCommand::Marker => Self::Marker,
+            SpaNodeCommand::ParamBegin => Self::ParamBegin,
+            SpaNodeCommand::ParamEnd => Self::ParamEnd,
+            SpaNodeCommand::RequestProcess => Self::RequestProcess,
+            SpaNodeCommand::User => return None,
+        })
+    }
+
+    /// A `Spa:Pod:Object:Command:Node` object
+    pub fn to_pod(self) -> pod::serialize::OwnedPod {
+        pod::Builder::with(|b| {
+            b.write_object_with(SpaType::CommandNode, self.id() as u32, |_| {});
+        })
+    }
+
+    /// Commands that are not known, or user defined ones, are returned as `Unknown`
+    pub fn from_pod(pod: &PodDeserializer) -> pod::deserialize::Result<SpaEnum<Self>> {
+        let obj = pod.as_object()?;
+        if obj.object_ty() != SpaEnum::Value(SpaType::CommandNode) {
+            return Err(pod::DeserializeError::UnexpectedType {
+                expected: SpaType::CommandNode,
+                got: obj.object_ty(),
+            });
+        }
+
+        let id = obj.object_id();
+        Ok(SpaEnum::<SpaNodeCommand>::from_raw(id)
+            .ok()
+            .and_then(Self::from_id)
+            .map_or(SpaEnum::Unknown(id), SpaEnum::Value))
+    }
+}
 
 pub mod methods {
     use super::*;
@@ -28,6 +114,7 @@ pub mod methods {
 
     impl MethodSerializeSimple for SubscribeParams {
         const OPCODE: u8 = 1;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, buf: impl Write + Seek) {
             pod::Builder::new(buf).push_struct_with(|b| {
                 b.write_array_with(|b| {
@@ -88,6 +175,7 @@ pub mod methods {
 
     impl MethodSerializeSimple for SetParam {
         const OPCODE: u8 = 3;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, buf: impl Write + Seek) {
             pod::Builder::new(buf).push_struct_with(|b| {
                 b.write_id(self.id);
@@ -109,6 +197,7 @@ pub mod methods {
 
     impl MethodSerializeSimple for SendCommand {
         const OPCODE: u8 = 4;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, buf: impl Write + Seek) {
             pod::Builder::new(buf).push_struct_with(|b| {
                 b.write_pod(&self.command);
@@ -154,7 +243,10 @@ pub mod events {
     impl EventDeserialize for Info {
         const OPCODE: u8 = 0;
 
-        fn deserialize(pod: &mut PodDeserializer, fds: &[RawFd]) -> pod::deserialize::Result<Self> {
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
                 id: pod.pop_field()?.as_u32()?,
@@ -192,7 +284,10 @@ pub mod events {
     impl EventDeserialize for Param {
         const OPCODE: u8 = 1;
 
-        fn deserialize(pod: &mut PodDeserializer, fds: &[RawFd]) -> pod::deserialize::Result<Self> {
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
                 seq: pod.pop_field()?.as_i32()?,
@@ -213,6 +308,11 @@ pub enum Event {
     ///
     /// Event emitted as a result of the enum_params method.
     Param(events::Param),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
 }
 
 impl HasInterface for Event {
@@ -0,0 +1,247 @@
+use super::*;
+use libspa_consts::{SpaFraction, SpaProfiler, SpaType};
+
+pub mod methods {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct AddListener {}
+
+    impl MethodSerializeSimple for AddListener {
+        const OPCODE: u8 = 0;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            unreachable!()
+        }
+    }
+}
+
+pub mod events {
+    use super::*;
+
+    /// Profiling data of the graph cycles since the last event
+    ///
+    /// pod - a struct of `Spa:Pod:Object:Profiler` objects, see [`Profile::samples`]
+    #[derive(Debug, Clone)]
+    pub struct Profile {
+        pub pod: OwnedPod,
+    }
+
+    impl Profile {
+        /// Decode the samples, one per graph cycle of a driver
+        pub fn samples(&self) -> pod::deserialize::Result<Vec<Sample>> {
+            let mut samples = Vec::new();
+
+            for obj in self.pod.as_deserializer().as_struct()? {
+                let obj = obj.as_object()?;
+                if obj.object_ty() != SpaEnum::Value(SpaType::ObjectProfiler) {
+                    continue;
+                }
+
+                let mut sample = Sample::default();
+                for prop in obj {
+                    let mut pod = prop.value.as_struct()?;
+                    match SpaEnum::from_raw(prop.key) {
+                        SpaEnum::Value(SpaProfiler::Info) => {
+                            sample.info = Some(SampleInfo::parse(&mut pod)?);
+                        }
+                        SpaEnum::Value(SpaProfiler::Clock) => {
+                            sample.clock = Some(Clock::parse(&mut pod)?);
+                        }
+                        SpaEnum::Value(SpaProfiler::DriverBlock) => {
+                            sample.driver = Some(Block::parse(&mut pod)?);
+                        }
+                        SpaEnum::Value(SpaProfiler::FollowerBlock) => {
+                            sample.followers.push(Block::parse(&mut pod)?);
+                        }
+                        SpaEnum::Value(SpaProfiler::FollowerClock) => {
+                            sample.follower_clocks.push(FollowerClock::parse(&mut pod)?);
+                        }
+                        _ => {}
+                    }
+                }
+
+                samples.push(sample);
+            }
+
+            Ok(samples)
+        }
+    }
+
+    impl EventDeserialize for Profile {
+        const OPCODE: u8 = 0;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            let mut pod = pod.as_struct()?;
+            Ok(Self {
+                pod: pod.pop_field()?.to_owned(),
+            })
+        }
+    }
+
+    /// A single graph cycle of a driver and its followers
+    #[derive(Debug, Clone, Default)]
+    pub struct Sample {
+        pub info: Option<SampleInfo>,
+        pub clock: Option<Clock>,
+        pub driver: Option<Block>,
+        pub followers: Vec<Block>,
+        pub follower_clocks: Vec<FollowerClock>,
+    }
+
+    /// Counter and CPU load
+    #[derive(Debug, Clone)]
+    pub struct SampleInfo {
+        pub counter: i64,
+        /// Fast, medium and slow CPU load average
+        pub cpu_load: [f32; 3],
+        pub xrun_count: i32,
+    }
+
+    impl SampleInfo {
+        fn parse(pod: &mut PodStructDeserializer) -> pod::deserialize::Result<Self> {
+            Ok(Self {
+                counter: pod.pop_field()?.as_i64()?,
+                cpu_load: [
+                    pod.pop_field()?.as_f32()?,
+                    pod.pop_field()?.as_f32()?,
+                    pod.pop_field()?.as_f32()?,
+                ],
+                xrun_count: pod.pop_field()?.as_i32()?,
+            })
+        }
+    }
+
+    /// Clock of the driver, times are in nanoseconds
+    #[derive(Debug, Clone)]
+    pub struct Clock {
+        pub flags: u32,
+        pub id: u32,
+        pub name: String,
+        pub nsec: i64,
+        pub rate: SpaFraction,
+        pub position: i64,
+        pub duration: i64,
+        pub delay: i64,
+        pub rate_diff: f64,
+        pub next_nsec: i64,
+        /// Not sent by older daemons
+        pub transport_state: Option<i32>,
+        pub cycle: Option<i32>,
+        pub xrun_duration: Option<i64>,
+    }
+
+    impl Clock {
+        fn parse(pod: &mut PodStructDeserializer) -> pod::deserialize::Result<Self> {
+            Ok(Self {
+                flags: pod.pop_field()?.as_u32()?,
+                id: pod.pop_field()?.as_u32()?,
+                name: pod.pop_field()?.as_str()?.to_string(),
+                nsec: pod.pop_field()?.as_i64()?,
+                rate: pod.pop_field()?.as_fraction()?,
+                position: pod.pop_field()?.as_i64()?,
+                duration: pod.pop_field()?.as_i64()?,
+                delay: pod.pop_field()?.as_i64()?,
+                rate_diff: pod.pop_field()?.as_f64()?,
+                next_nsec: pod.pop_field()?.as_i64()?,
+                transport_state: pod.next().map(|v| v.as_i32()).transpose()?,
+                cycle: pod.next().map(|v| v.as_i32()).transpose()?,
+                xrun_duration: pod.next().map(|v| v.as_i64()).transpose()?,
+            })
+        }
+    }
+
+    /// Timing of a node in a graph cycle, times are in nanoseconds
+    #[derive(Debug, Clone)]
+    pub struct Block {
+        /// Id of the node
+        pub id: u32,
+        pub name: String,
+        pub prev_signal: i64,
+        /// When the node was scheduled
+        pub signal: i64,
+        /// When the node started processing
+        pub awake: i64,
+        /// When the node was done processing
+        pub finish: i64,
+        pub status: i32,
+        pub latency: SpaFraction,
+        /// Not sent by older daemons
+        pub xrun_count: Option<i32>,
+    }
+
+    impl Block {
+        /// Time between getting scheduled and starting to process
+        pub fn waiting(&self) -> i64 {
+            self.awake - self.signal
+        }
+
+        /// Time spent processing
+        pub fn busy(&self) -> i64 {
+            self.finish - self.awake
+        }
+
+        fn parse(pod: &mut PodStructDeserializer) -> pod::deserialize::Result<Self> {
+            Ok(Self {
+                id: pod.pop_field()?.as_u32()?,
+                name: pod.pop_field()?.as_str()?.to_string(),
+                prev_signal: pod.pop_field()?.as_i64()?,
+                signal: pod.pop_field()?.as_i64()?,
+                awake: pod.pop_field()?.as_i64()?,
+                finish: pod.pop_field()?.as_i64()?,
+                status: pod.pop_field()?.as_i32()?,
+                latency: pod.pop_field()?.as_fraction()?,
+                xrun_count: pod.next().map(|v| v.as_i32()).transpose()?,
+            })
+        }
+    }
+
+    /// Clock of a follower that is driven by a different clock
+    #[derive(Debug, Clone)]
+    pub struct FollowerClock {
+        pub id: u32,
+        pub name: String,
+        pub nsec: i64,
+        pub rate: SpaFraction,
+        pub position: i64,
+        pub duration: i64,
+        pub delay: i64,
+        pub rate_diff: f64,
+        pub next_nsec: i64,
+        pub xrun_duration: i64,
+    }
+
+    impl FollowerClock {
+        fn parse(pod: &mut PodStructDeserializer) -> pod::deserialize::Result<Self> {
+            Ok(Self {
+                id: pod.pop_field()?.as_u32()?,
+                name: pod.pop_field()?.as_str()?.to_string(),
+                nsec: pod.pop_field()?.as_i64()?,
+                rate: pod.pop_field()?.as_fraction()?,
+                position: pod.pop_field()?.as_i64()?,
+                duration: pod.pop_field()?.as_i64()?,
+                delay: pod.pop_field()?.as_i64()?,
+                rate_diff: pod.pop_field()?.as_f64()?,
+                next_nsec: pod.pop_field()?.as_i64()?,
+                xrun_duration: pod.pop_field()?.as_i64()?,
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone, pod_derive::EventDeserialize)]
+pub enum Event {
+    /// Profiling data
+    Profile(events::Profile),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
+}
+
+impl HasInterface for Event {
+    const INTERFACE: &'static str = "Profiler";
+}
@@ -56,6 +56,7 @@ pub mod methods {
 
     impl MethodSerializeSimple for Destroy {
         const OPCODE: u8 = 2;
+        const SINCE: u32 = 3;
         fn serialize_simple(&self, buf: impl Write + Seek) {
             pod::Builder::new(buf).push_struct_with(|b| {
                 b.write_u32(self.id);
@@ -93,7 +94,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            fds: &[RawFd],
+            fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -122,7 +123,7 @@ pub mod events {
 
         fn deserialize(
             pod: &mut pod::PodDeserializer,
-            _fds: &[RawFd],
+            _fds: &mut [Option<OwnedFd>],
         ) -> pod::deserialize::Result<Self> {
             let mut pod = pod.as_struct()?;
             Ok(Self {
@@ -144,6 +145,11 @@ pub enum Event {
     /// Emitted when a global object was removed from the registry.
     /// If the client has any bindings to the global, it should destroy those.
     GlobalRemove(events::GlobalRemove),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
 }
 
 impl HasInterface for Event {
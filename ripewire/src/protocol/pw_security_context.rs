@@ -0,0 +1,57 @@
+use super::*;
+
+pub mod methods {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct AddListener {}
+
+    impl MethodSerializeSimple for AddListener {
+        const OPCODE: u8 = 0;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            unreachable!()
+        }
+    }
+
+    /// Create a new security context
+    ///
+    /// Makes the daemon accept clients on `listen_fd`, a bound and listening unix socket.
+    /// The clients get `properties` applied, which can't be changed by the clients themselves,
+    /// eg. `pipewire.sec.engine` and `pipewire.access`.
+    ///
+    /// Once `close_fd`, usually the read end of a pipe, becomes readable or gets closed,
+    /// the daemon stops listening and closes the socket.
+    #[derive(Debug)]
+    pub struct Create<'a> {
+        pub listen_fd: BorrowedFd<'a>,
+        pub close_fd: BorrowedFd<'a>,
+        pub properties: PwDictionary,
+    }
+
+    impl MethodSerialize for Create<'_> {
+        const OPCODE: u8 = 1;
+
+        fn serialize<'a>(&'a self, mut buf: impl Write + Seek, fds: &mut Vec<BorrowedFd<'a>>) {
+            pod::Builder::new(&mut buf).push_struct_with(|b| {
+                for fd in [self.listen_fd, self.close_fd] {
+                    let fd_id = fds.len() as u64;
+                    fds.push(fd);
+                    b.write_fd(fd_id);
+                }
+                write_dict(b, &self.properties);
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone, pod_derive::EventDeserialize)]
+pub enum Event {
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
+}
+
+impl HasInterface for Event {
+    const INTERFACE: &'static str = "SecurityContext";
+}
@@ -0,0 +1,210 @@
+use super::*;
+
+pub mod methods {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct AddListener {}
+
+    impl MethodSerializeSimple for AddListener {
+        const OPCODE: u8 = 0;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            unreachable!()
+        }
+    }
+
+    /// Subscribe to parameter changes
+    ///
+    /// Automatically emit param events for the given ids when they are changed.
+    ///
+    /// ids - an array of param ids
+    #[derive(Debug, Clone)]
+    pub struct SubscribeParams {
+        pub ids: Vec<Id>,
+    }
+
+    impl MethodSerializeSimple for SubscribeParams {
+        const OPCODE: u8 = 1;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            pod::Builder::new(buf).push_struct_with(|b| {
+                b.write_array_with(|b| {
+                    for id in self.ids.iter() {
+                        b.write_id(id);
+                    }
+                });
+            });
+        }
+    }
+
+    /// Enumerate parameters
+    ///
+    /// Start enumeration of parameters. For each param, a param event will be emitted.
+    ///
+    /// seq - a sequence number to place in the reply
+    /// id - the parameter id to enum or PW_ID_ANY for all
+    /// start - the start index or 0 for the first param
+    /// num - the maximum number of params to retrieve
+    /// filter - a param filter or NULL
+    #[derive(Debug, Clone)]
+    pub struct EnumParams {
+        pub seq: i32,
+        pub id: Id,
+        pub index: u32,
+        pub num: u32,
+        pub filter: pod::serialize::OwnedPod,
+    }
+
+    impl MethodSerializeSimple for EnumParams {
+        const OPCODE: u8 = 2;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            pod::Builder::new(buf).push_struct_with(|b| {
+                b.write_i32(self.seq);
+                b.write_id(self.id);
+                b.write_u32(self.index);
+                b.write_u32(self.num);
+                b.write_pod(&self.filter);
+            });
+        }
+    }
+
+    /// Set a parameter
+    ///
+    /// id - the parameter id to set
+    /// flags - extra parameter flags
+    /// param - the parameter to set
+    #[derive(Debug, Clone)]
+    pub struct SetParam {
+        pub id: Id,
+        pub flags: u32,
+        pub param: pod::serialize::OwnedPod,
+    }
+
+    impl MethodSerializeSimple for SetParam {
+        const OPCODE: u8 = 3;
+        fn serialize_simple(&self, buf: impl Write + Seek) {
+            pod::Builder::new(buf).push_struct_with(|b| {
+                b.write_id(self.id);
+                b.write_u32(self.flags);
+                b.write_pod(&self.param);
+            });
+        }
+    }
+}
+
+pub use events::ChangeMask;
+pub mod events {
+    use super::*;
+
+    bitflags::bitflags! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct ChangeMask: u32 {
+            const PROPS = 1 << 0;
+            const PARAMS = 1 << 1;
+        }
+    }
+
+    /// Notify session info
+    ///
+    /// Also sent by the session manager through [`ClientSession`] updates.
+    ///
+    /// [`ClientSession`]: crate::protocol::pw_client_session
+    #[derive(Debug, Clone)]
+    pub struct Info {
+        /// Version of the info struct, currently 0
+        pub version: u32,
+        pub id: u32,
+        pub change_mask: ChangeMask,
+        pub props: PwDictionary,
+        pub params: Vec<ParamInfo>,
+    }
+
+    impl Info {
+        pub(crate) fn parse(pod: &mut PodStructDeserializer) -> pod::deserialize::Result<Self> {
+            Ok(Self {
+                version: pod.pop_field()?.as_u32()?,
+                id: pod.pop_field()?.as_u32()?,
+                change_mask: ChangeMask::from_bits_retain(pod.pop_field()?.as_u32()?),
+                props: parse_dict(&mut pod.pop_field()?.as_struct()?)?,
+                params: parse_params(&mut pod.pop_field()?.as_struct()?)?,
+            })
+        }
+
+        pub(crate) fn serialize(&self, b: &mut pod::Builder<impl Write + Seek>) {
+            b.push_struct_with(|b| {
+                b.write_u32(self.version);
+                b.write_u32(self.id);
+                b.write_u32(self.change_mask.bits());
+                write_dict(b, &self.props);
+                write_params(b, &self.params);
+            });
+        }
+    }
+
+    impl EventDeserialize for Info {
+        const OPCODE: u8 = 0;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            Self::parse(&mut pod.as_struct()?)
+        }
+    }
+
+    /// Notify a param
+    ///
+    /// Event emitted as a result of the enum_params method.
+    ///
+    /// seq - the sequence number of the request
+    /// id - the param id
+    /// index - the param index
+    /// next - the param index of the next param
+    /// param - the parameter
+    #[derive(Debug, Clone)]
+    pub struct Param {
+        pub seq: i32,
+        pub id: SpaEnum<SpaParamType>,
+        pub index: u32,
+        pub next: u32,
+        /// Bytes of a spa object
+        pub params: OwnedPod,
+    }
+
+    impl EventDeserialize for Param {
+        const OPCODE: u8 = 1;
+
+        fn deserialize(
+            pod: &mut PodDeserializer,
+            fds: &mut [Option<OwnedFd>],
+        ) -> pod::deserialize::Result<Self> {
+            let mut pod = pod.as_struct()?;
+            Ok(Self {
+                seq: pod.pop_field()?.as_i32()?,
+                id: SpaEnum::from_raw(pod.pop_field()?.as_id()?),
+                index: pod.pop_field()?.as_u32()?,
+                next: pod.pop_field()?.as_u32()?,
+                params: pod.pop_field()?.to_owned(),
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone, pod_derive::EventDeserialize)]
+pub enum Event {
+    /// Notify session info
+    Info(events::Info),
+
+    /// Notify a session param
+    ///
+    /// Event emitted as a result of the enum_params method.
+    Param(events::Param),
+
+    /// The object is gone and its id got freed, emitted locally, see [`Proxy::destroy`]
+    ///
+    /// [`Proxy::destroy`]: crate::proxy::Proxy::destroy
+    Removed,
+}
+
+impl HasInterface for Event {
+    const INTERFACE: &'static str = "Session";
+}
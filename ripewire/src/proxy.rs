@@ -1,22 +1,38 @@
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap, os::fd::BorrowedFd, rc::Rc};
 
-use libspa_consts::{SpaDirection, SpaEnum, SpaParamType};
+use libspa_consts::{PwEndpointLinkState, SpaDirection, SpaEnum, SpaParamType};
 use pod::Id;
 
 use crate::{
-    context::Context,
+    connection::FooterClient,
+    context::{Context, Dispatch, ListenerHandle, ProxyError},
     object_map::ObjectType,
+    params::{ParamQuery, ParamsProxy},
     protocol::{
-        self, pw_client, pw_client_node, pw_core, pw_device, pw_link, pw_node, pw_port,
-        pw_registry, MethodSerialize,
+        self, pw_client, pw_client_endpoint, pw_client_node, pw_client_session, pw_core, pw_device,
+        pw_endpoint, pw_endpoint_link, pw_endpoint_stream, pw_factory, pw_link, pw_metadata,
+        pw_module, pw_node, pw_port, pw_profiler, pw_registry, pw_security_context, pw_session,
+        AnyEvent, FromAnyEventRef, MethodSerialize, PwDictionary,
     },
+    Error, Result,
 };
 
 pub trait Proxy {
-    type Event;
+    type Event: TryFrom<AnyEvent, Error = AnyEvent> + Into<AnyEvent> + FromAnyEventRef;
+
+    /// Highest version of the interface that is implemented
+    const VERSION: u32;
 
     fn from_id(id: ObjectId) -> Self;
     fn id(&self) -> ObjectId;
+
+    /// Destroy the object, no more events are dispatched for it
+    ///
+    /// The id stays reserved until the daemon acknowledges with `RemoveId`, the handler
+    /// then gets the `Removed` pseudo event.
+    fn destroy<D>(&self, context: &mut Context<D>) -> Result<Seq> {
+        context.destroy(self)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -34,6 +50,27 @@ impl ObjectId {
     }
 }
 
+/// Sequence number of a sent method, the daemon refers to it in errors and replies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Seq(u32);
+
+impl Seq {
+    pub fn new(seq: u32) -> Self {
+        Self(seq)
+    }
+
+    pub fn raw(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Id of a param object, as passed to the `set_param` methods
+fn param_id(param: &pod::serialize::OwnedPod) -> Result<u32> {
+    let (obj, _) = pod::PodDeserializer::new(&param.0);
+    let obj = obj.as_object().map_err(Error::InvalidParam)?;
+    Ok(obj.object_id())
+}
+
 #[derive(Debug, Clone)]
 pub struct PwCore {
     object_id: ObjectId,
@@ -50,59 +87,73 @@ impl PwCore {
         self.object_id.clone()
     }
 
-    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) {
-        let (msg, fds) = protocol::create_msg_with_fds(self.object_id.object_id, &message);
-        context.send_msg(&msg, fds.as_slice()).unwrap();
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
     }
 
-    pub fn hello<D>(&self, context: &mut Context<D>) {
-        self.send(context, pw_core::methods::Hello { version: 4 });
+    pub fn hello<D>(&self, context: &mut Context<D>) -> Result<Seq> {
+        self.send(
+            context,
+            pw_core::methods::Hello {
+                version: Self::VERSION,
+            },
+        )
     }
 
-    pub fn sync<D>(&self, context: &mut Context<D>, id: u32, seq: u32) {
-        self.send(context, pw_core::methods::Sync { id, seq });
+    pub fn sync<D>(&self, context: &mut Context<D>, id: u32, seq: u32) -> Result<Seq> {
+        self.send(context, pw_core::methods::Sync { id, seq })
     }
 
-    pub fn pong<D>(&self, context: &mut Context<D>, id: u32, seq: u32) {
-        self.send(context, pw_core::methods::Pong { id, seq });
+    pub fn pong<D>(&self, context: &mut Context<D>, id: u32, seq: u32) -> Result<Seq> {
+        self.send(context, pw_core::methods::Pong { id, seq })
     }
 
-    pub fn get_registry<D>(&self, context: &mut Context<D>) -> PwRegistry {
-        let new_id = context.new_object(ObjectType::Registry).protocol_id();
+    pub fn get_registry<D>(&self, context: &mut Context<D>) -> Result<PwRegistry> {
+        let new_id = context
+            .new_object(ObjectType::Registry, PwRegistry::VERSION)
+            .protocol_id();
 
         self.send(
             context,
-            pw_core::methods::GetRegistry { version: 3, new_id },
-        );
+            pw_core::methods::GetRegistry {
+                version: PwRegistry::VERSION,
+                new_id,
+            },
+        )?;
 
-        PwRegistry::new(new_id)
+        Ok(PwRegistry::new(new_id))
     }
 
-    pub fn destroy_object<D>(&self, context: &mut Context<D>, object_id: ObjectId) {
+    pub fn destroy_object<D>(&self, context: &mut Context<D>, object_id: ObjectId) -> Result<Seq> {
         self.send(
             context,
             pw_core::methods::Destroy {
                 id: object_id.protocol_id(),
             },
-        );
+        )
     }
 
     pub fn create_object<I: Proxy, D>(
         &self,
         context: &mut Context<D>,
         mut data: pw_core::methods::CreateObject,
-    ) -> I {
-        let new_id = context.new_object(ObjectType::from_interface_name(&data.interface));
+    ) -> Result<I> {
+        data.version = data.version.min(I::VERSION);
+        let new_id = context.new_object(
+            ObjectType::from_interface_name(&data.interface),
+            data.version,
+        );
         data.new_id = new_id.object_id;
 
-        self.send(context, data);
+        self.send(context, data)?;
 
-        I::from_id(new_id)
+        Ok(I::from_id(new_id))
     }
 }
 
 impl Proxy for PwCore {
     type Event = pw_core::Event;
+    const VERSION: u32 = 4;
 
     fn from_id(object_id: ObjectId) -> Self {
         Self { object_id }
@@ -129,26 +180,156 @@ impl PwClient {
         self.object_id.clone()
     }
 
-    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) {
-        let (msg, fds) = protocol::create_msg_with_fds(self.object_id.object_id, &message);
-        context.send_msg(&msg, fds.as_slice()).unwrap();
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
     }
 
     pub fn update_properties<D>(
         &self,
         context: &mut Context<D>,
         properties: HashMap<String, String>,
-    ) {
-        self.send(context, pw_client::methods::UpdateProperties { properties });
+    ) -> Result<Seq> {
+        self.send(context, pw_client::methods::UpdateProperties { properties })
+    }
+
+    pub fn get_permissions<D>(
+        &self,
+        context: &mut Context<D>,
+        index: u32,
+        num: u32,
+    ) -> Result<Seq> {
+        self.send(context, pw_client::methods::GetPermissions { index, num })
+    }
+
+    /// Read the whole permission table of the client, `cb` gets it once the last page arrived
+    ///
+    /// Pages through the table with [`pw_client::methods::GetPermissions`], replies to
+    /// other requests are left alone. Each page is followed by a sync, so `cb` is called even
+    /// when the daemon emits nothing. The daemon rejecting a page is passed to `cb` as
+    /// [`Error::Proxy`], failing to send the request for the next page as that error.
+    pub fn collect_permissions<D, F>(&self, context: &mut Context<D>, cb: F) -> Result<()>
+    where
+        F: FnOnce(&mut D, &mut Context<D>, PwClient, Result<pw_client::PermissionTable>) + 'static,
+    {
+        let permissions: Rc<RefCell<Vec<pw_client::Permission>>> = Rc::default();
+
+        let object_listener = context.add_listener(self, {
+            let permissions = permissions.clone();
+            move |_, _, _, event| {
+                let pw_client::Event::Permissions(event) = event else {
+                    return;
+                };
+                let mut permissions = permissions.borrow_mut();
+                if event.index as usize == permissions.len() {
+                    permissions.extend(event.permissions.iter().cloned());
+                }
+            }
+        })?;
+
+        let mut page_index = 0;
+        let mut page = self.request_permissions_page(context, page_index)?;
+        let mut object_listener = Some(object_listener);
+        let mut cb = Some(cb);
+        let core_listener: Rc<RefCell<Option<ListenerHandle>>> = Rc::default();
+
+        let handle = context.add_listener(&context.core(), {
+            let core_listener = core_listener.clone();
+            let client = self.clone();
+            move |state, ctx, _, event| {
+                let (seq, sync_seq) = page;
+                let result = match event {
+                    pw_core::Event::Done(done)
+                        if done.id == Some(client.object_id.protocol_id())
+                            && done.seq as u32 == sync_seq.raw() =>
+                    {
+                        // A full page might be followed by more, a short one is the last
+                        let received = permissions.borrow().len() as u32;
+                        if received == page_index + PERMISSIONS_PAGE {
+                            match client.request_permissions_page(ctx, received) {
+                                Ok(next) => {
+                                    page_index = received;
+                                    page = next;
+                                    return;
+                                }
+                                Err(err) => Err(err),
+                            }
+                        } else {
+                            Ok(pw_client::PermissionTable::from_permissions(
+                                std::mem::take(&mut *permissions.borrow_mut()),
+                            ))
+                        }
+                    }
+                    pw_core::Event::Error(error)
+                        if error.id == client.object_id.protocol_id()
+                            && error.seq as u32 == seq.raw() =>
+                    {
+                        Err(Error::Proxy(ProxyError {
+                            seq,
+                            res: error.res,
+                            message: error.message.clone(),
+                        }))
+                    }
+                    _ => return,
+                };
+
+                object_listener.take();
+                core_listener.borrow_mut().take();
+                if let Some(cb) = cb.take() {
+                    cb(state, ctx, client.clone(), result);
+                }
+            }
+        })?;
+        *core_listener.borrow_mut() = Some(handle);
+
+        Ok(())
+    }
+
+    /// Request the page of permissions starting at `index`, followed by a sync that is done
+    /// once the page was emitted
+    ///
+    /// Returns the seqs of the request and of the sync.
+    fn request_permissions_page<D>(
+        &self,
+        context: &mut Context<D>,
+        index: u32,
+    ) -> Result<(Seq, Seq)> {
+        let seq = self.get_permissions(context, index, PERMISSIONS_PAGE)?;
+        let sync_seq = context.next_seq();
+        context
+            .core()
+            .sync(context, self.object_id.protocol_id(), sync_seq.raw())?;
+        Ok((seq, sync_seq))
+    }
+
+    /// Replace the permissions of the given globals, see [`pw_client::PermissionTable`]
+    pub fn update_permissions<D>(
+        &self,
+        context: &mut Context<D>,
+        permissions: impl IntoIterator<Item = pw_client::Permission>,
+    ) -> Result<Seq> {
+        self.send(
+            context,
+            pw_client::methods::UpdatePermissions(permissions.into_iter().collect()),
+        )
     }
 
-    pub fn get_permissions<D>(&self, context: &mut Context<D>, index: u32, num: u32) {
-        self.send(context, pw_client::methods::GetPermissions { index, num });
+    /// Set the permissions on a single global, [`pw_client::ID_ANY`] sets the default
+    pub fn set_permissions<D>(
+        &self,
+        context: &mut Context<D>,
+        id: u32,
+        permissions: pw_client::PermissionFlags,
+    ) -> Result<Seq> {
+        self.update_permissions(context, [pw_client::Permission::new(id, permissions)])
     }
 }
 
+/// Entries requested at once by [`PwClient::collect_permissions`]
+const PERMISSIONS_PAGE: u32 = 64;
+
 impl Proxy for PwClient {
     type Event = pw_client::Event;
+    const VERSION: u32 = 3;
 
     fn from_id(object_id: ObjectId) -> Self {
         Self { object_id }
@@ -175,38 +356,65 @@ impl PwRegistry {
         self.object_id.clone()
     }
 
-    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) {
-        let (msg, fds) = protocol::create_msg_with_fds(self.object_id.object_id, &message);
-        context.send_msg(&msg, fds.as_slice()).unwrap();
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
     }
 
-    pub fn bind<I: Proxy, D>(
+    /// Bind to `global`, its events get dispatched through `D`'s [`Dispatch`] impl with `data`
+    ///
+    /// ```ignore
+    /// let node: PwNode = registry.bind(ctx, global, NodeData::default())?;
+    /// ```
+    pub fn bind<I, U, D>(
         &self,
         context: &mut Context<D>,
         global: &pw_registry::events::Global,
-    ) -> I {
-        let new_id = context.new_object(global.interface.clone()).protocol_id();
-
-        self.send(
-            context,
-            pw_registry::methods::Bind {
-                id: global.id,
-                interface: global.interface.as_interface_name().to_string(),
-                version: global.version,
-                new_id,
-            },
-        );
+        data: U,
+    ) -> Result<I>
+    where
+        I: Proxy + 'static,
+        U: 'static,
+        D: Dispatch<I, U>,
+    {
+        let proxy: I = self.bind_untyped(context, global)?;
+        context.set_object_dispatch(&proxy, data);
+        Ok(proxy)
+    }
 
-        I::from_id(ObjectId::new(new_id))
+    /// Bind to `global` without attaching a handler, see [`Context::set_object_callback`]
+    pub fn bind_untyped<I: Proxy, D>(
+        &self,
+        context: &mut Context<D>,
+        global: &pw_registry::events::Global,
+    ) -> Result<I> {
+        // Never bind with a version that we don't understand
+        let version = global.version.min(I::VERSION);
+        let new_id = context
+            .new_object(global.interface.clone(), version)
+            .protocol_id();
+
+        let bind = pw_registry::methods::Bind {
+            id: global.id,
+            interface: global.interface.as_interface_name().to_string(),
+            version,
+            new_id,
+        };
+        let footers = [FooterClient::Generation(context.generation())];
+        let (msg, fds) =
+            protocol::create_msg_with_footers(self.object_id.object_id, &bind, &footers);
+        context.send_msg(&msg, &fds)?;
+
+        Ok(I::from_id(ObjectId::new(new_id)))
     }
 
-    pub fn destroy_global<D>(&self, context: &mut Context<D>, global: u32) {
-        self.send(context, pw_registry::methods::Destroy { id: global });
+    pub fn destroy_global<D>(&self, context: &mut Context<D>, global: u32) -> Result<Seq> {
+        self.send(context, pw_registry::methods::Destroy { id: global })
     }
 }
 
 impl Proxy for PwRegistry {
     type Event = pw_registry::Event;
+    const VERSION: u32 = 3;
 
     fn from_id(object_id: ObjectId) -> Self {
         Self { object_id }
@@ -224,6 +432,7 @@ pub struct PwDevice {
 
 impl Proxy for PwDevice {
     type Event = pw_device::Event;
+    const VERSION: u32 = 3;
 
     fn from_id(object_id: ObjectId) -> Self {
         Self { object_id }
@@ -239,29 +448,23 @@ impl PwDevice {
         self.object_id.clone()
     }
 
-    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) {
-        let (msg, fds) = protocol::create_msg_with_fds(self.object_id.object_id, &message);
-        context.send_msg(&msg, fds.as_slice()).unwrap();
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
     }
 
-    pub fn enum_param<D>(&self, context: &mut Context<D>, id: SpaParamType) {
-        self.send(
-            context,
-            pw_device::methods::EnumParams {
-                seq: 0,
-                id: Id(id as u32),
-                index: 0,
-                num: 0,
-                filter: pod::Builder::with(|b| {
-                    b.write_none();
-                }),
-            },
-        );
+    /// Enumerate all params of type `id`, the `Param` events carry the returned seq
+    ///
+    /// See [`ParamsProxy`] for paginated and filtered enumerations.
+    pub fn enum_param<D>(&self, context: &mut Context<D>, id: SpaParamType) -> Result<Seq> {
+        self.enum_params_query(context, &ParamQuery::new(id))
     }
 
-    pub fn set_param<D>(&self, context: &mut Context<D>, param: pod::serialize::OwnedPod) {
-        let (obj, _) = pod::PodDeserializer::new(&param.0);
-        let id = obj.as_object().unwrap().object_id();
+    pub fn set_param<D>(
+        &self,
+        context: &mut Context<D>,
+        param: pod::serialize::OwnedPod,
+    ) -> Result<Seq> {
+        let id = param_id(&param)?;
 
         self.send(
             context,
@@ -270,7 +473,7 @@ impl PwDevice {
                 flags: 0,
                 param,
             },
-        );
+        )
     }
 }
 
@@ -281,6 +484,7 @@ pub struct PwNode {
 
 impl Proxy for PwNode {
     type Event = pw_node::Event;
+    const VERSION: u32 = 3;
 
     fn from_id(object_id: ObjectId) -> Self {
         Self { object_id }
@@ -296,29 +500,23 @@ impl PwNode {
         self.object_id.clone()
     }
 
-    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) {
-        let (msg, fds) = protocol::create_msg_with_fds(self.object_id.object_id, &message);
-        context.send_msg(&msg, fds.as_slice()).unwrap();
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
     }
 
-    pub fn enum_param<D>(&self, context: &mut Context<D>, id: SpaParamType) {
-        self.send(
-            context,
-            pw_node::methods::EnumParams {
-                seq: 0,
-                id: Id(id as u32),
-                index: 0,
-                num: 0,
-                filter: pod::Builder::with(|b| {
-                    b.write_none();
-                }),
-            },
-        );
+    /// Enumerate all params of type `id`, the `Param` events carry the returned seq
+    ///
+    /// See [`ParamsProxy`] for paginated and filtered enumerations.
+    pub fn enum_param<D>(&self, context: &mut Context<D>, id: SpaParamType) -> Result<Seq> {
+        self.enum_params_query(context, &ParamQuery::new(id))
     }
 
-    pub fn set_param<D>(&self, context: &mut Context<D>, param: pod::serialize::OwnedPod) {
-        let (obj, _) = pod::PodDeserializer::new(&param.0);
-        let id = obj.as_object().unwrap().object_id();
+    pub fn set_param<D>(
+        &self,
+        context: &mut Context<D>,
+        param: pod::serialize::OwnedPod,
+    ) -> Result<Seq> {
+        let id = param_id(&param)?;
 
         self.send(
             context,
@@ -327,7 +525,23 @@ impl PwNode {
                 flags: 0,
                 param,
             },
-        );
+        )
+    }
+
+    /// Suspend, pause, start... the node
+    ///
+    /// This requires X and W permissions on the node.
+    pub fn send_command<D>(
+        &self,
+        context: &mut Context<D>,
+        command: pw_node::NodeCommand,
+    ) -> Result<Seq> {
+        self.send(
+            context,
+            pw_node::methods::SendCommand {
+                command: command.to_pod(),
+            },
+        )
     }
 }
 
@@ -338,6 +552,7 @@ pub struct PwClientNode {
 
 impl Proxy for PwClientNode {
     type Event = pw_client_node::Event;
+    const VERSION: u32 = 6;
 
     fn from_id(object_id: ObjectId) -> Self {
         Self { object_id }
@@ -353,9 +568,8 @@ impl PwClientNode {
         self.object_id.clone()
     }
 
-    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) {
-        let (msg, fds) = protocol::create_msg_with_fds(self.object_id.object_id, &message);
-        context.send_msg(&msg, fds.as_slice()).unwrap();
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
     }
 
     pub fn port_buffers<D>(
@@ -364,7 +578,7 @@ impl PwClientNode {
         direction: SpaDirection,
         port_id: u32,
         mix_id: u32,
-    ) {
+    ) -> Result<Seq> {
         self.send(
             context,
             pw_client_node::methods::PortBuffers {
@@ -373,7 +587,7 @@ impl PwClientNode {
                 mix_id,
                 buffers: vec![],
             },
-        );
+        )
     }
 }
 
@@ -384,6 +598,7 @@ pub struct PwLink {
 
 impl Proxy for PwLink {
     type Event = pw_link::Event;
+    const VERSION: u32 = 3;
 
     fn from_id(object_id: ObjectId) -> Self {
         Self { object_id }
@@ -407,6 +622,7 @@ pub struct PwPort {
 
 impl Proxy for PwPort {
     type Event = pw_port::Event;
+    const VERSION: u32 = 3;
 
     fn from_id(object_id: ObjectId) -> Self {
         Self { object_id }
@@ -422,23 +638,670 @@ impl PwPort {
         self.object_id.clone()
     }
 
-    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) {
-        let (msg, fds) = protocol::create_msg_with_fds(self.object_id.object_id, &message);
-        context.send_msg(&msg, fds.as_slice()).unwrap();
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
+    }
+
+    /// Enumerate all params of type `id`, the `Param` events carry the returned seq
+    ///
+    /// See [`ParamsProxy`] for paginated and filtered enumerations.
+    pub fn enum_params<D>(&self, context: &mut Context<D>, id: SpaParamType) -> Result<Seq> {
+        self.enum_params_query(context, &ParamQuery::new(id))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PwFactory {
+    object_id: ObjectId,
+}
+
+impl Proxy for PwFactory {
+    type Event = pw_factory::Event;
+    const VERSION: u32 = 3;
+
+    fn from_id(object_id: ObjectId) -> Self {
+        Self { object_id }
+    }
+
+    fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+impl PwFactory {
+    pub fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PwModule {
+    object_id: ObjectId,
+}
+
+impl Proxy for PwModule {
+    type Event = pw_module::Event;
+    const VERSION: u32 = 3;
+
+    fn from_id(object_id: ObjectId) -> Self {
+        Self { object_id }
+    }
+
+    fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+impl PwModule {
+    pub fn id(&self) -> ObjectId {
+        self.object_id.clone()
     }
+}
 
-    pub fn enum_params<D>(&self, context: &mut Context<D>, id: SpaParamType) {
+#[derive(Debug, Clone)]
+pub struct PwMetadata {
+    object_id: ObjectId,
+}
+
+impl Proxy for PwMetadata {
+    type Event = pw_metadata::Event;
+    const VERSION: u32 = 3;
+
+    fn from_id(object_id: ObjectId) -> Self {
+        Self { object_id }
+    }
+
+    fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+impl PwMetadata {
+    pub fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
+    }
+
+    /// Set `key` of `subject` to `value` of type `ty`, a `None` value removes the key
+    ///
+    /// ```ignore
+    /// metadata.set_property(
+    ///     ctx,
+    ///     0,
+    ///     "default.configured.audio.sink",
+    ///     Some(pw_metadata::TYPE_JSON),
+    ///     Some(r#"{ "name": "alsa_output.pci-0000_00_1f.3.analog-stereo" }"#),
+    /// )?;
+    /// ```
+    pub fn set_property<D>(
+        &self,
+        context: &mut Context<D>,
+        subject: u32,
+        key: &str,
+        ty: Option<&str>,
+        value: Option<&str>,
+    ) -> Result<Seq> {
         self.send(
             context,
-            pw_device::methods::EnumParams {
-                seq: 0,
-                id: Id(id as u32),
-                index: 0,
-                num: 0,
-                filter: pod::Builder::with(|b| {
-                    b.write_none();
-                }),
+            pw_metadata::methods::SetProperty {
+                subject,
+                key: Some(key.to_string()),
+                ty: ty.map(ToString::to_string),
+                value: value.map(ToString::to_string),
+            },
+        )
+    }
+
+    /// Remove all properties
+    pub fn clear<D>(&self, context: &mut Context<D>) -> Result<Seq> {
+        self.send(context, pw_metadata::methods::Clear {})
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PwProfiler {
+    object_id: ObjectId,
+}
+
+impl Proxy for PwProfiler {
+    type Event = pw_profiler::Event;
+    const VERSION: u32 = 3;
+
+    fn from_id(object_id: ObjectId) -> Self {
+        Self { object_id }
+    }
+
+    fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+impl PwProfiler {
+    pub fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PwSecurityContext {
+    object_id: ObjectId,
+}
+
+impl Proxy for PwSecurityContext {
+    type Event = pw_security_context::Event;
+    const VERSION: u32 = 3;
+
+    fn from_id(object_id: ObjectId) -> Self {
+        Self { object_id }
+    }
+
+    fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+impl PwSecurityContext {
+    pub fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
+    }
+
+    /// Let the daemon accept clients with restricted `properties` on `listen_fd`,
+    /// until `close_fd` becomes readable, see [`pw_security_context::methods::Create`]
+    ///
+    /// The fds are duplicated, they can be closed once this returns.
+    pub fn create<D>(
+        &self,
+        context: &mut Context<D>,
+        listen_fd: BorrowedFd,
+        close_fd: BorrowedFd,
+        properties: PwDictionary,
+    ) -> Result<Seq> {
+        self.send(
+            context,
+            pw_security_context::methods::Create {
+                listen_fd,
+                close_fd,
+                properties,
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PwSession {
+    object_id: ObjectId,
+}
+
+impl Proxy for PwSession {
+    type Event = pw_session::Event;
+    const VERSION: u32 = 0;
+
+    fn from_id(object_id: ObjectId) -> Self {
+        Self { object_id }
+    }
+
+    fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+impl PwSession {
+    pub fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
+    }
+
+    pub fn subscribe_params<D>(
+        &self,
+        context: &mut Context<D>,
+        ids: &[SpaParamType],
+    ) -> Result<Seq> {
+        self.send(
+            context,
+            pw_session::methods::SubscribeParams {
+                ids: ids.iter().map(|id| Id(*id as u32)).collect(),
+            },
+        )
+    }
+
+    /// Enumerate all params of type `id`, the `Param` events carry the returned seq
+    ///
+    /// See [`ParamsProxy`] for paginated and filtered enumerations.
+    pub fn enum_params<D>(&self, context: &mut Context<D>, id: SpaParamType) -> Result<Seq> {
+        self.enum_params_query(context, &ParamQuery::new(id))
+    }
+
+    pub fn set_param<D>(
+        &self,
+        context: &mut Context<D>,
+        param: pod::serialize::OwnedPod,
+    ) -> Result<Seq> {
+        let id = param_id(&param)?;
+
+        self.send(
+            context,
+            pw_session::methods::SetParam {
+                id: Id(id),
+                flags: 0,
+                param,
             },
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PwEndpoint {
+    object_id: ObjectId,
+}
+
+impl Proxy for PwEndpoint {
+    type Event = pw_endpoint::Event;
+    const VERSION: u32 = 0;
+
+    fn from_id(object_id: ObjectId) -> Self {
+        Self { object_id }
+    }
+
+    fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+impl PwEndpoint {
+    pub fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
+    }
+
+    pub fn subscribe_params<D>(
+        &self,
+        context: &mut Context<D>,
+        ids: &[SpaParamType],
+    ) -> Result<Seq> {
+        self.send(
+            context,
+            pw_endpoint::methods::SubscribeParams {
+                ids: ids.iter().map(|id| Id(*id as u32)).collect(),
+            },
+        )
+    }
+
+    /// Enumerate all params of type `id`, the `Param` events carry the returned seq
+    ///
+    /// See [`ParamsProxy`] for paginated and filtered enumerations.
+    pub fn enum_params<D>(&self, context: &mut Context<D>, id: SpaParamType) -> Result<Seq> {
+        self.enum_params_query(context, &ParamQuery::new(id))
+    }
+
+    pub fn set_param<D>(
+        &self,
+        context: &mut Context<D>,
+        param: pod::serialize::OwnedPod,
+    ) -> Result<Seq> {
+        let id = param_id(&param)?;
+
+        self.send(
+            context,
+            pw_endpoint::methods::SetParam {
+                id: Id(id),
+                flags: 0,
+                param,
+            },
+        )
+    }
+
+    /// Ask the session manager to link this endpoint, see [`pw_endpoint::methods::CreateLink`]
+    pub fn create_link<D>(
+        &self,
+        context: &mut Context<D>,
+        properties: PwDictionary,
+    ) -> Result<Seq> {
+        self.send(context, pw_endpoint::methods::CreateLink { properties })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PwEndpointStream {
+    object_id: ObjectId,
+}
+
+impl Proxy for PwEndpointStream {
+    type Event = pw_endpoint_stream::Event;
+    const VERSION: u32 = 0;
+
+    fn from_id(object_id: ObjectId) -> Self {
+        Self { object_id }
+    }
+
+    fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+impl PwEndpointStream {
+    pub fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
+    }
+
+    pub fn subscribe_params<D>(
+        &self,
+        context: &mut Context<D>,
+        ids: &[SpaParamType],
+    ) -> Result<Seq> {
+        self.send(
+            context,
+            pw_endpoint_stream::methods::SubscribeParams {
+                ids: ids.iter().map(|id| Id(*id as u32)).collect(),
+            },
+        )
+    }
+
+    /// Enumerate all params of type `id`, the `Param` events carry the returned seq
+    ///
+    /// See [`ParamsProxy`] for paginated and filtered enumerations.
+    pub fn enum_params<D>(&self, context: &mut Context<D>, id: SpaParamType) -> Result<Seq> {
+        self.enum_params_query(context, &ParamQuery::new(id))
+    }
+
+    pub fn set_param<D>(
+        &self,
+        context: &mut Context<D>,
+        param: pod::serialize::OwnedPod,
+    ) -> Result<Seq> {
+        let id = param_id(&param)?;
+
+        self.send(
+            context,
+            pw_endpoint_stream::methods::SetParam {
+                id: Id(id),
+                flags: 0,
+                param,
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PwEndpointLink {
+    object_id: ObjectId,
+}
+
+impl Proxy for PwEndpointLink {
+    type Event = pw_endpoint_link::Event;
+    const VERSION: u32 = 0;
+
+    fn from_id(object_id: ObjectId) -> Self {
+        Self { object_id }
+    }
+
+    fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+impl PwEndpointLink {
+    pub fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
+    }
+
+    pub fn subscribe_params<D>(
+        &self,
+        context: &mut Context<D>,
+        ids: &[SpaParamType],
+    ) -> Result<Seq> {
+        self.send(
+            context,
+            pw_endpoint_link::methods::SubscribeParams {
+                ids: ids.iter().map(|id| Id(*id as u32)).collect(),
+            },
+        )
+    }
+
+    /// Enumerate all params of type `id`, the `Param` events carry the returned seq
+    ///
+    /// See [`ParamsProxy`] for paginated and filtered enumerations.
+    pub fn enum_params<D>(&self, context: &mut Context<D>, id: SpaParamType) -> Result<Seq> {
+        self.enum_params_query(context, &ParamQuery::new(id))
+    }
+
+    pub fn set_param<D>(
+        &self,
+        context: &mut Context<D>,
+        param: pod::serialize::OwnedPod,
+    ) -> Result<Seq> {
+        let id = param_id(&param)?;
+
+        self.send(
+            context,
+            pw_endpoint_link::methods::SetParam {
+                id: Id(id),
+                flags: 0,
+                param,
+            },
+        )
+    }
+
+    /// Ask the session manager to move the link to `state`
+    pub fn request_state<D>(
+        &self,
+        context: &mut Context<D>,
+        state: PwEndpointLinkState,
+    ) -> Result<Seq> {
+        self.send(context, pw_endpoint_link::methods::RequestState { state })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PwClientEndpoint {
+    object_id: ObjectId,
+}
+
+impl Proxy for PwClientEndpoint {
+    type Event = pw_client_endpoint::Event;
+    const VERSION: u32 = 0;
+
+    fn from_id(object_id: ObjectId) -> Self {
+        Self { object_id }
+    }
+
+    fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+impl PwClientEndpoint {
+    pub fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
+    }
+
+    /// Update the params and info of the exported endpoint, `None` leaves them unchanged
+    pub fn update<D>(
+        &self,
+        context: &mut Context<D>,
+        params: Option<Vec<pod::serialize::OwnedPod>>,
+        info: Option<pw_endpoint::events::Info>,
+    ) -> Result<Seq> {
+        let mut change_mask = pw_client_endpoint::methods::UpdateChangeMask::empty();
+        change_mask.set(
+            pw_client_endpoint::methods::UpdateChangeMask::PARAMS,
+            params.is_some(),
+        );
+        change_mask.set(
+            pw_client_endpoint::methods::UpdateChangeMask::INFO,
+            info.is_some(),
+        );
+
+        self.send(
+            context,
+            pw_client_endpoint::methods::Update {
+                change_mask,
+                params: params.unwrap_or_default(),
+                info,
+            },
+        )
+    }
+
+    /// Add or update a stream of the exported endpoint, `None` leaves the params or info unchanged
+    pub fn stream_update<D>(
+        &self,
+        context: &mut Context<D>,
+        stream_id: u32,
+        params: Option<Vec<pod::serialize::OwnedPod>>,
+        info: Option<pw_endpoint_stream::events::Info>,
+    ) -> Result<Seq> {
+        let mut change_mask = pw_client_endpoint::methods::UpdateChangeMask::empty();
+        change_mask.set(
+            pw_client_endpoint::methods::UpdateChangeMask::PARAMS,
+            params.is_some(),
+        );
+        change_mask.set(
+            pw_client_endpoint::methods::UpdateChangeMask::INFO,
+            info.is_some(),
         );
+
+        self.send(
+            context,
+            pw_client_endpoint::methods::StreamUpdate {
+                stream_id,
+                change_mask,
+                params: params.unwrap_or_default(),
+                info,
+            },
+        )
+    }
+
+    /// Remove a stream of the exported endpoint
+    pub fn remove_stream<D>(&self, context: &mut Context<D>, stream_id: u32) -> Result<Seq> {
+        self.send(
+            context,
+            pw_client_endpoint::methods::StreamUpdate {
+                stream_id,
+                change_mask: pw_client_endpoint::methods::UpdateChangeMask::DESTROYED,
+                params: Vec::new(),
+                info: None,
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PwClientSession {
+    object_id: ObjectId,
+}
+
+impl Proxy for PwClientSession {
+    type Event = pw_client_session::Event;
+    const VERSION: u32 = 0;
+
+    fn from_id(object_id: ObjectId) -> Self {
+        Self { object_id }
+    }
+
+    fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+}
+
+impl PwClientSession {
+    pub fn id(&self) -> ObjectId {
+        self.object_id.clone()
+    }
+
+    pub fn send<D>(&self, context: &mut Context<D>, message: impl MethodSerialize) -> Result<Seq> {
+        context.send_method(&self.object_id, &message)
+    }
+
+    /// Update the params and info of the exported session, `None` leaves them unchanged
+    pub fn update<D>(
+        &self,
+        context: &mut Context<D>,
+        params: Option<Vec<pod::serialize::OwnedPod>>,
+        info: Option<pw_session::events::Info>,
+    ) -> Result<Seq> {
+        let mut change_mask = pw_client_session::methods::UpdateChangeMask::empty();
+        change_mask.set(
+            pw_client_session::methods::UpdateChangeMask::PARAMS,
+            params.is_some(),
+        );
+        change_mask.set(
+            pw_client_session::methods::UpdateChangeMask::INFO,
+            info.is_some(),
+        );
+
+        self.send(
+            context,
+            pw_client_session::methods::Update {
+                change_mask,
+                params: params.unwrap_or_default(),
+                info,
+            },
+        )
+    }
+
+    /// Add or update a link of the exported session, `None` leaves the params or info unchanged
+    pub fn link_update<D>(
+        &self,
+        context: &mut Context<D>,
+        link_id: u32,
+        params: Option<Vec<pod::serialize::OwnedPod>>,
+        info: Option<pw_endpoint_link::events::Info>,
+    ) -> Result<Seq> {
+        let mut change_mask = pw_client_session::methods::UpdateChangeMask::empty();
+        change_mask.set(
+            pw_client_session::methods::UpdateChangeMask::PARAMS,
+            params.is_some(),
+        );
+        change_mask.set(
+            pw_client_session::methods::UpdateChangeMask::INFO,
+            info.is_some(),
+        );
+
+        self.send(
+            context,
+            pw_client_session::methods::LinkUpdate {
+                link_id,
+                change_mask,
+                params: params.unwrap_or_default(),
+                info,
+            },
+        )
+    }
+
+    /// Remove a link of the exported session
+    pub fn remove_link<D>(&self, context: &mut Context<D>, link_id: u32) -> Result<Seq> {
+        self.send(
+            context,
+            pw_client_session::methods::LinkUpdate {
+                link_id,
+                change_mask: pw_client_session::methods::UpdateChangeMask::DESTROYED,
+                params: Vec::new(),
+                info: None,
+            },
+        )
     }
 }
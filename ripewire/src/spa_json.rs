@@ -0,0 +1,363 @@
+//! SPA-JSON, the relaxed JSON dialect used by PipeWire for metadata values and config files
+//!
+//! Compared to JSON, keys and strings don't need quotes, `=` can be used instead of `:`,
+//! commas are optional and `#` starts a comment that runs until the end of the line.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpaJson {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<SpaJson>),
+    /// Keys in the order they appear in
+    Object(Vec<(String, SpaJson)>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid SPA-JSON at byte {offset}")]
+pub struct ParseError {
+    pub offset: usize,
+}
+
+impl SpaJson {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            input,
+            pos: 0,
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_separators();
+
+        if parser.pos != input.len() {
+            return Err(parser.error());
+        }
+
+        Ok(value)
+    }
+
+    /// Value of `key`, if this is an object
+    pub fn get(&self, key: &str) -> Option<&SpaJson> {
+        match self {
+            Self::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[SpaJson]> {
+        match self {
+            Self::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// Formats as strict JSON, which is valid SPA-JSON as well
+impl fmt::Display for SpaJson {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(v) => write!(f, "{v}"),
+            Self::Number(v) => write!(f, "{v}"),
+            Self::String(v) => write_string(f, v),
+            Self::Array(values) => {
+                f.write_str("[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                f.write_str("]")
+            }
+            Self::Object(entries) => {
+                f.write_str("{")?;
+                for (i, (k, v)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write_string(f, k)?;
+                    write!(f, ": {v}")?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, v: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in v.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// Deepest nesting of arrays and objects, deeper input is refused instead of overflowing the stack
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// Arrays and objects the parser is in
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self) -> ParseError {
+        ParseError { offset: self.pos }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// Skip whitespace, comments and the optional `,`, `:` and `=` separators
+    fn skip_separators(&mut self) {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\r' | '\n' | ',' | ':' | '=' => {
+                    self.bump();
+                }
+                '#' => while !matches!(self.bump(), None | Some('\n')) {},
+                _ => break,
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<SpaJson, ParseError> {
+        self.skip_separators();
+
+        match self.peek().ok_or_else(|| self.error())? {
+            '{' => self.nested(Self::object),
+            '[' => self.nested(Self::array),
+            '"' => Ok(SpaJson::String(self.string()?)),
+            _ => {
+                let word = self.bare()?;
+                Ok(match word {
+                    "null" => SpaJson::Null,
+                    "true" => SpaJson::Bool(true),
+                    "false" => SpaJson::Bool(false),
+                    // Words like `inf` or `nan` are strings, same as in spa_json
+                    word if is_number(word) => match word.parse() {
+                        Ok(v) => SpaJson::Number(v),
+                        Err(_) => SpaJson::String(word.to_string()),
+                    },
+                    word => SpaJson::String(word.to_string()),
+                })
+            }
+        }
+    }
+
+    /// Parse an array or object, one level deeper
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<SpaJson, ParseError>,
+    ) -> Result<SpaJson, ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error());
+        }
+
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> Result<SpaJson, ParseError> {
+        // Opening brace
+        self.bump();
+
+        let mut entries = Vec::new();
+        loop {
+            self.skip_separators();
+            match self.peek().ok_or_else(|| self.error())? {
+                '}' => {
+                    self.bump();
+                    return Ok(SpaJson::Object(entries));
+                }
+                '"' => {
+                    let key = self.string()?;
+                    entries.push((key, self.value()?));
+                }
+                _ => {
+                    let key = self.bare()?.to_string();
+                    entries.push((key, self.value()?));
+                }
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<SpaJson, ParseError> {
+        // Opening bracket
+        self.bump();
+
+        let mut values = Vec::new();
+        loop {
+            self.skip_separators();
+            if self.peek() == Some(']') {
+                self.bump();
+                return Ok(SpaJson::Array(values));
+            }
+            values.push(self.value()?);
+        }
+    }
+
+    /// An unquoted word, runs until the next whitespace or separator
+    fn bare(&mut self) -> Result<&str, ParseError> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\r' | '\n' | ',' | ':' | '=' | '#' | '{' | '}' | '[' | ']' | '"' => {
+                    break
+                }
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+
+        if self.pos == start {
+            return Err(self.error());
+        }
+
+        Ok(&self.input[start..self.pos])
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        // Opening quote
+        self.bump();
+
+        let mut out = String::new();
+        loop {
+            match self.bump().ok_or_else(|| self.error())? {
+                '"' => return Ok(out),
+                '\\' => {
+                    let c = match self.bump().ok_or_else(|| self.error())? {
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'u' => self.unicode_escape()?,
+                        c => c,
+                    };
+                    out.push(c);
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    /// The code after `\u`, UTF-16 surrogate pairs are combined
+    fn unicode_escape(&mut self) -> Result<char, ParseError> {
+        let high = self.hex4()?;
+        if !(0xd800..0xdc00).contains(&high) {
+            return Ok(char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+
+        // A high surrogate has to be followed by an escaped low one
+        let start = self.pos;
+        if self.input[start..].starts_with("\\u") {
+            self.pos += 2;
+            let low = self.hex4()?;
+            if (0xdc00..0xe000).contains(&low) {
+                let code = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                return Ok(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+            // Not a pair, the second escape stands on its own
+            self.pos = start;
+        }
+
+        Ok(char::REPLACEMENT_CHARACTER)
+    }
+
+    /// 4 hex digits
+    fn hex4(&mut self) -> Result<u32, ParseError> {
+        let hex = self
+            .input
+            .get(self.pos..self.pos + 4)
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error())?;
+        let code = u32::from_str_radix(hex, 16).map_err(|_| self.error())?;
+        self.pos += 4;
+        Ok(code)
+    }
+}
+
+/// Does `word` follow the JSON number syntax, `-?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?`
+fn is_number(word: &str) -> bool {
+    let mut bytes = word.as_bytes();
+
+    let digits = |bytes: &mut &[u8]| {
+        let n = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+        *bytes = &bytes[n..];
+        n
+    };
+
+    if let [b'-', rest @ ..] = bytes {
+        bytes = rest;
+    }
+    match bytes {
+        [b'0', rest @ ..] => bytes = rest,
+        [b'1'..=b'9', ..] => {
+            digits(&mut bytes);
+        }
+        _ => return false,
+    }
+    if let [b'.', rest @ ..] = bytes {
+        bytes = rest;
+        if digits(&mut bytes) == 0 {
+            return false;
+        }
+    }
+    if let [b'e' | b'E', rest @ ..] = bytes {
+        bytes = rest;
+        if let [b'+' | b'-', rest @ ..] = bytes {
+            bytes = rest;
+        }
+        if digits(&mut bytes) == 0 {
+            return false;
+        }
+    }
+
+    bytes.is_empty()
+}
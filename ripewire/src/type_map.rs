@@ -0,0 +1,251 @@
+//! Dynamic type ids of the v0 native protocol
+//!
+//! v0 peers have no fixed type ids. They register the names of the types they use with the core
+//! `UpdateTypes` method (or event, on the daemon side) and refer to a type by its index in that
+//! list afterwards, in `Id` pods, object types and property keys. [`TypeMap`] keeps the list of
+//! a peer, [`type_from_v0_name`] resolves a name to the id it has now.
+
+use libspa_consts::{
+    SpaAudioFormat, SpaDataType, SpaFormat, SpaMediaSubtype, SpaMediaType, SpaMetaType,
+    SpaParamBuffers, SpaParamMeta, SpaParamType, SpaType, SpaVideoFormat,
+};
+
+/// Registering more types than this is refused, to not allocate whatever a peer asks for
+const MAX_TYPES: usize = 4096;
+
+/// Types registered by a v0 peer, indexed by the id the peer refers to them with
+#[derive(Debug, Clone, Default)]
+pub struct TypeMap {
+    types: Vec<Option<String>>,
+}
+
+impl TypeMap {
+    /// Register `types` starting at `first_id`, as sent with `UpdateTypes`
+    ///
+    /// Ids past an internal limit are ignored.
+    pub fn update<I>(&mut self, first_id: u32, types: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        for (id, name) in (first_id as usize..MAX_TYPES).zip(types) {
+            if self.types.len() <= id {
+                self.types.resize(id + 1, None);
+            }
+            self.types[id] = Some(name.into());
+        }
+    }
+
+    /// Name the peer registered as `id`
+    pub fn name(&self, id: u32) -> Option<&str> {
+        self.types.get(id as usize)?.as_deref()
+    }
+
+    /// Current id of the type the peer registered as `id`
+    ///
+    /// `None` if the peer did not register `id` or the type has no fixed id, like the interfaces,
+    /// which are referred to by their name nowadays.
+    pub fn translate(&self, id: u32) -> Option<u32> {
+        type_from_v0_name(self.name(id)?)
+    }
+}
+
+/// Current id of the v0 type `name`, eg. `Spa:Enum:ParamId:EnumFormat`
+pub fn type_from_v0_name(name: &str) -> Option<u32> {
+    V0_TYPES
+        .iter()
+        .find(|(v0_name, _)| *v0_name == name)
+        .map(|(_, id)| *id)
+}
+
+/// v0 type names and their current ids
+///
+/// Covers the types used by params, formats and buffer negotiation.
+const V0_TYPES: &[(&str, u32)] = &[
+    // Object types
+    (
+        "Spa:Pod:Object:Param:PropInfo",
+        SpaType::ObjectPropInfo as u32,
+    ),
+    ("Spa:Pod:Object:Param:Props", SpaType::ObjectProps as u32),
+    ("Spa:Pod:Object:Param:Format", SpaType::ObjectFormat as u32),
+    (
+        "Spa:Pod:Object:Param:Buffers",
+        SpaType::ObjectParamBuffers as u32,
+    ),
+    ("Spa:Pod:Object:Param:Meta", SpaType::ObjectParamMeta as u32),
+    ("Spa:Pod:Object:Param:IO", SpaType::ObjectParamIo as u32),
+    // Param ids
+    ("Spa:Enum:ParamId:PropInfo", SpaParamType::PropInfo as u32),
+    ("Spa:Enum:ParamId:Props", SpaParamType::Props as u32),
+    (
+        "Spa:Enum:ParamId:EnumFormat",
+        SpaParamType::EnumFormat as u32,
+    ),
+    ("Spa:Enum:ParamId:Format", SpaParamType::Format as u32),
+    ("Spa:Enum:ParamId:Buffers", SpaParamType::Buffers as u32),
+    ("Spa:Enum:ParamId:Meta", SpaParamType::Meta as u32),
+    ("Spa:Enum:ParamId:IO", SpaParamType::Io as u32),
+    // Media types
+    ("Spa:Enum:MediaType:audio", SpaMediaType::Audio as u32),
+    ("Spa:Enum:MediaType:video", SpaMediaType::Video as u32),
+    ("Spa:Enum:MediaType:image", SpaMediaType::Image as u32),
+    ("Spa:Enum:MediaType:binary", SpaMediaType::Binary as u32),
+    ("Spa:Enum:MediaType:stream", SpaMediaType::Stream as u32),
+    ("Spa:Enum:MediaSubtype:raw", SpaMediaSubtype::Raw as u32),
+    ("Spa:Enum:MediaSubtype:mp3", SpaMediaSubtype::Mp3 as u32),
+    ("Spa:Enum:MediaSubtype:aac", SpaMediaSubtype::Aac as u32),
+    (
+        "Spa:Enum:MediaSubtype:vorbis",
+        SpaMediaSubtype::Vorbis as u32,
+    ),
+    ("Spa:Enum:MediaSubtype:sbc", SpaMediaSubtype::Sbc as u32),
+    ("Spa:Enum:MediaSubtype:h264", SpaMediaSubtype::H264 as u32),
+    ("Spa:Enum:MediaSubtype:mjpg", SpaMediaSubtype::Mjpg as u32),
+    // Format properties
+    (
+        "Spa:Pod:Object:Param:Format:Audio:format",
+        SpaFormat::AudioFormat as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Audio:flags",
+        SpaFormat::AudioFlags as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Audio:rate",
+        SpaFormat::AudioRate as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Audio:channels",
+        SpaFormat::AudioChannels as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:format",
+        SpaFormat::VideoFormat as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:size",
+        SpaFormat::VideoSize as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:framerate",
+        SpaFormat::VideoFramerate as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:max-framerate",
+        SpaFormat::VideoMaxFramerate as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:views",
+        SpaFormat::VideoViews as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:interlace-mode",
+        SpaFormat::VideoInterlaceMode as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:pixel-aspect-ratio",
+        SpaFormat::VideoPixelAspectRatio as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:multiview-mode",
+        SpaFormat::VideoMultiviewMode as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:multiview-flags",
+        SpaFormat::VideoMultiviewFlags as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:chroma-site",
+        SpaFormat::VideoChromaSite as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:color-range",
+        SpaFormat::VideoColorRange as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:color-matrix",
+        SpaFormat::VideoColorMatrix as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:transfer-function",
+        SpaFormat::VideoTransferFunction as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Format:Video:color-primaries",
+        SpaFormat::VideoColorPrimaries as u32,
+    ),
+    // Buffers and meta properties
+    (
+        "Spa:Pod:Object:Param:Buffers:buffers",
+        SpaParamBuffers::Buffers as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Buffers:size",
+        SpaParamBuffers::Size as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Buffers:stride",
+        SpaParamBuffers::Stride as u32,
+    ),
+    (
+        "Spa:Pod:Object:Param:Buffers:align",
+        SpaParamBuffers::Align as u32,
+    ),
+    ("Spa:Pod:Object:Param:Meta:type", SpaParamMeta::Type as u32),
+    ("Spa:Pod:Object:Param:Meta:size", SpaParamMeta::Size as u32),
+    ("Spa:Pointer:Meta:Header", SpaMetaType::Header as u32),
+    ("Spa:Pointer:Meta:VideoCrop", SpaMetaType::VideoCrop as u32),
+    (
+        "Spa:Pointer:Meta:VideoDamage",
+        SpaMetaType::VideoDamage as u32,
+    ),
+    ("Spa:Pointer:Meta:Bitmap", SpaMetaType::Bitmap as u32),
+    ("Spa:Pointer:Meta:Cursor", SpaMetaType::Cursor as u32),
+    ("Spa:Enum:DataType:MemPtr", SpaDataType::MemPtr as u32),
+    ("Spa:Enum:DataType:Fd:MemFd", SpaDataType::MemFd as u32),
+    ("Spa:Enum:DataType:Fd:DmaBuf", SpaDataType::DmaBuf as u32),
+    ("Spa:Enum:DataType:MemId", SpaDataType::MemId as u32),
+    // Audio formats
+    ("Spa:Enum:AudioFormat:S8", SpaAudioFormat::S8 as u32),
+    ("Spa:Enum:AudioFormat:U8", SpaAudioFormat::U8 as u32),
+    ("Spa:Enum:AudioFormat:S16LE", SpaAudioFormat::S16Le as u32),
+    ("Spa:Enum:AudioFormat:S16BE", SpaAudioFormat::S16Be as u32),
+    ("Spa:Enum:AudioFormat:U16LE", SpaAudioFormat::U16Le as u32),
+    ("Spa:Enum:AudioFormat:U16BE", SpaAudioFormat::U16Be as u32),
+    (
+        "Spa:Enum:AudioFormat:S24_32LE",
+        SpaAudioFormat::S2432Le as u32,
+    ),
+    (
+        "Spa:Enum:AudioFormat:S24_32BE",
+        SpaAudioFormat::S2432Be as u32,
+    ),
+    ("Spa:Enum:AudioFormat:S32LE", SpaAudioFormat::S32Le as u32),
+    ("Spa:Enum:AudioFormat:S32BE", SpaAudioFormat::S32Be as u32),
+    ("Spa:Enum:AudioFormat:S24LE", SpaAudioFormat::S24Le as u32),
+    ("Spa:Enum:AudioFormat:S24BE", SpaAudioFormat::S24Be as u32),
+    ("Spa:Enum:AudioFormat:F32LE", SpaAudioFormat::F32Le as u32),
+    ("Spa:Enum:AudioFormat:F32BE", SpaAudioFormat::F32Be as u32),
+    ("Spa:Enum:AudioFormat:F64LE", SpaAudioFormat::F64Le as u32),
+    ("Spa:Enum:AudioFormat:F64BE", SpaAudioFormat::F64Be as u32),
+    // Video formats
+    ("Spa:Enum:VideoFormat:I420", SpaVideoFormat::I420 as u32),
+    ("Spa:Enum:VideoFormat:YV12", SpaVideoFormat::Yv12 as u32),
+    ("Spa:Enum:VideoFormat:YUY2", SpaVideoFormat::Yuy2 as u32),
+    ("Spa:Enum:VideoFormat:UYVY", SpaVideoFormat::Uyvy as u32),
+    ("Spa:Enum:VideoFormat:AYUV", SpaVideoFormat::Ayuv as u32),
+    ("Spa:Enum:VideoFormat:RGBx", SpaVideoFormat::RgBx as u32),
+    ("Spa:Enum:VideoFormat:BGRx", SpaVideoFormat::BgRx as u32),
+    ("Spa:Enum:VideoFormat:xRGB", SpaVideoFormat::XRgb as u32),
+    ("Spa:Enum:VideoFormat:xBGR", SpaVideoFormat::XBgr as u32),
+    ("Spa:Enum:VideoFormat:RGBA", SpaVideoFormat::Rgba as u32),
+    ("Spa:Enum:VideoFormat:BGRA", SpaVideoFormat::Bgra as u32),
+    ("Spa:Enum:VideoFormat:ARGB", SpaVideoFormat::Argb as u32),
+    ("Spa:Enum:VideoFormat:ABGR", SpaVideoFormat::Abgr as u32),
+    ("Spa:Enum:VideoFormat:RGB", SpaVideoFormat::Rgb as u32),
+    ("Spa:Enum:VideoFormat:BGR", SpaVideoFormat::Bgr as u32),
+    ("Spa:Enum:VideoFormat:NV12", SpaVideoFormat::Nv12 as u32),
+    ("Spa:Enum:VideoFormat:NV21", SpaVideoFormat::Nv21 as u32),
+    ("Spa:Enum:VideoFormat:GRAY8", SpaVideoFormat::Gray8 as u32),
+];